@@ -0,0 +1,98 @@
+use crate::bitboard::{bishop_attacks, rook_attacks, squares, KING_ATTACKS, KNIGHT_ATTACKS, PAWN_ATTACKS};
+use crate::{board_index, board_index_reverse, Board, Color, Piece, PieceType};
+
+pub(crate) const KING_MOVES: [(i8, i8); 8] = [
+    (1, 1),
+    (1, -1),
+    (1, 0),
+    (0, 1),
+    (0, -1),
+    (-1, 1),
+    (-1, -1),
+    (-1, 0),
+];
+
+pub(crate) const KNIGHT_MOVES: [(i8, i8); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+
+#[inline]
+/// Returns the square `delta` (file, rank) away from `index`, or None if it is off the board
+pub(crate) fn offset(index: usize, delta: (i8, i8)) -> Option<usize> {
+    let (file, rank) = board_index_reverse(index);
+
+    let file = file as i8 + delta.0;
+    let rank = rank as i8 + delta.1;
+
+    if !(0..8).contains(&file) || !(0..8).contains(&rank) {
+        return None;
+    }
+
+    Some(board_index(file as usize, rank as usize))
+}
+
+/// Returns whether a piece of this type attacks along straight lines
+pub(crate) fn is_slider(piece_type: PieceType) -> bool {
+    matches!(piece_type, PieceType::Rook | PieceType::Bishop | PieceType::Queen)
+}
+
+/// Returns whether the three squares lie on one rank, file or diagonal
+pub(crate) fn aligned(a: usize, b: usize, c: usize) -> bool {
+    let (a_file, a_rank) = board_index_reverse(a);
+    let (b_file, b_rank) = board_index_reverse(b);
+    let (c_file, c_rank) = board_index_reverse(c);
+
+    let (ab_file, ab_rank) = (b_file as i32 - a_file as i32, b_rank as i32 - a_rank as i32);
+    let (ac_file, ac_rank) = (c_file as i32 - a_file as i32, c_rank as i32 - a_rank as i32);
+
+    let is_line = |file: i32, rank: i32| file == 0 || rank == 0 || file.abs() == rank.abs();
+
+    // The squares must be on one line through a, and that line must be a chess line
+    ab_file * ac_rank == ab_rank * ac_file && is_line(ab_file, ab_rank) && is_line(ac_file, ac_rank)
+}
+
+impl Board {
+    /// Returns the pieces of `color` that attack `target`, as a bitboard
+    pub(crate) fn attackers_bitboard(&self, target: usize, color: Color) -> u64 {
+        let occupied = self.occupied();
+        let pieces = |piece_type: PieceType| self.pieces[piece_type as usize] & self.colors[color as usize];
+        let queens = pieces(PieceType::Queen);
+
+        // A pawn attacks `target` from the squares a pawn of the other color on `target` would attack
+        (PAWN_ATTACKS[!color as usize][target] & pieces(PieceType::Pawn))
+            | (KNIGHT_ATTACKS[target] & pieces(PieceType::Knight))
+            | (KING_ATTACKS[target] & pieces(PieceType::King))
+            | (rook_attacks(target, occupied) & (pieces(PieceType::Rook) | queens))
+            | (bishop_attacks(target, occupied) & (pieces(PieceType::Bishop) | queens))
+    }
+
+    /// Returns the squares of all pieces of `color` that attack `target`
+    pub(crate) fn attackers(&self, target: usize, color: Color) -> Vec<usize> {
+        squares(self.attackers_bitboard(target, color)).collect()
+    }
+
+    /// Returns whether any piece of `color` attacks `target`
+    pub(crate) fn is_attacked(&self, target: usize, color: Color) -> bool {
+        self.attackers_bitboard(target, color) != 0
+    }
+
+    /// Returns the square of the king of `color`, if there is one
+    pub(crate) fn king_square(&self, color: Color) -> Option<usize> {
+        squares(self.bitboard(Piece::new(PieceType::King, color))).next()
+    }
+
+    /// Returns the squares of the pieces giving check to the side to move
+    pub(crate) fn checkers(&self) -> Vec<usize> {
+        match self.king_square(self.turn) {
+            Some(king) => self.attackers(king, !self.turn),
+            None => Vec::new(),
+        }
+    }
+}
@@ -1,8 +1,49 @@
 use std::hint::black_box;
+use std::time::Instant;
 
-fn main() {
-    for _ in 0..10_000_000 {
-        black_box(fen_generator::random_fen());
-    }
+use fen_generator::{perft, Board, RandomConfig};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+const RANDOM_BOARDS: u32 = 2_000_000;
+const LEGALITY_ROUNDS: u32 = 20;
+const PERFT_DEPTH: u32 = 5;
+
+/// Runs `f` once and prints how long it took in total and per item
+fn time(name: &str, items: u64, f: impl FnOnce()) {
+    let start = Instant::now();
+    f();
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<24} {:>8.3} s  {:>8.1} ns/item",
+        name, elapsed.as_secs_f64(), elapsed.as_nanos() as f64 / items as f64);
 }
 
+fn main() {
+    let config = RandomConfig::default();
+
+    // Placing the pieces and checking legality, until a legal board comes out
+    time("random boards", RANDOM_BOARDS as u64, || {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..RANDOM_BOARDS {
+            black_box(Board::random(&mut rng, &config));
+        }
+    });
+
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    let boards: Vec<Board> = (0..100_000).map(|_| Board::random(&mut rng, &config)).collect();
+    time("legality checks", LEGALITY_ROUNDS as u64 * boards.len() as u64, || {
+        for _ in 0..LEGALITY_ROUNDS {
+            for board in &boards {
+                black_box(black_box(board).is_legal());
+            }
+        }
+    });
+
+    let start = Board::start_position();
+    let nodes = perft(&start, PERFT_DEPTH);
+    time("perft start position", nodes, || {
+        black_box(perft(black_box(&start), PERFT_DEPTH));
+    });
+}
@@ -0,0 +1,164 @@
+use crate::{Board, Color, Piece, N_SQUARES};
+
+/// Directions as (file, rank) steps. The first four go to higher square numbers,
+/// the last four to lower ones
+const DIRECTIONS: [(i8, i8); 8] = [
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (-1, 1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (1, -1),
+];
+
+const ROOK_RAYS: [usize; 4] = [0, 2, 4, 6];
+const BISHOP_RAYS: [usize; 4] = [1, 3, 5, 7];
+
+/// The second to the seventh rank, where pawns can stand
+pub(crate) const PAWN_RANKS: u64 = 0x00FF_FFFF_FFFF_FF00;
+
+/// Returns the bitboard with only `square` set
+#[inline]
+pub(crate) const fn bit(square: usize) -> u64 {
+    1 << square
+}
+
+/// Returns the squares `deltas` away from every square, dropping the ones off the board
+const fn step_attacks(deltas: &[(i8, i8)]) -> [u64; N_SQUARES] {
+    let mut table = [0; N_SQUARES];
+
+    let mut square = 0;
+    while square < N_SQUARES {
+        let mut i = 0;
+        while i < deltas.len() {
+            let file = (square % 8) as i8 + deltas[i].0;
+            let rank = (square / 8) as i8 + deltas[i].1;
+            if file >= 0 && file < 8 && rank >= 0 && rank < 8 {
+                table[square] |= bit((rank * 8 + file) as usize);
+            }
+            i += 1;
+        }
+        square += 1;
+    }
+    table
+}
+
+pub(crate) static KING_ATTACKS: [u64; N_SQUARES] = step_attacks(&crate::attacks::KING_MOVES);
+
+pub(crate) static KNIGHT_ATTACKS: [u64; N_SQUARES] = step_attacks(&crate::attacks::KNIGHT_MOVES);
+
+/// Squares a pawn attacks, indexed by its `Color` and then its square
+pub(crate) static PAWN_ATTACKS: [[u64; N_SQUARES]; 2] = [
+    step_attacks(&[(-1, 1), (1, 1)]),
+    step_attacks(&[(-1, -1), (1, -1)]),
+];
+
+/// Every square from a square to the edge of the board in each of `DIRECTIONS`,
+/// not counting the square itself
+static RAYS: [[u64; N_SQUARES]; 8] = {
+    let mut rays = [[0; N_SQUARES]; 8];
+
+    let mut direction = 0;
+    while direction < 8 {
+        let (file_step, rank_step) = DIRECTIONS[direction];
+
+        let mut square = 0;
+        while square < N_SQUARES {
+            let mut file = (square % 8) as i8 + file_step;
+            let mut rank = (square / 8) as i8 + rank_step;
+            while file >= 0 && file < 8 && rank >= 0 && rank < 8 {
+                rays[direction][square] |= bit((rank * 8 + file) as usize);
+                file += file_step;
+                rank += rank_step;
+            }
+            square += 1;
+        }
+        direction += 1;
+    }
+    rays
+};
+
+/// Returns the squares a slider on `square` reaches in `direction`, up to and including
+/// the first occupied square
+#[inline]
+fn ray_attacks(square: usize, direction: usize, occupied: u64) -> u64 {
+    let ray = RAYS[direction][square];
+    let blockers = ray & occupied;
+    if blockers == 0 {
+        return ray;
+    }
+
+    // The nearest blocker is the lowest bit on rays going up and the highest on rays going down
+    let blocker = if direction < 4 {
+        blockers.trailing_zeros() as usize
+    } else {
+        63 - blockers.leading_zeros() as usize
+    };
+    ray ^ RAYS[direction][blocker]
+}
+
+/// Returns the squares a rook on `square` attacks with the given pieces on the board
+#[inline]
+pub(crate) fn rook_attacks(square: usize, occupied: u64) -> u64 {
+    ROOK_RAYS.iter().fold(0, |attacks, &direction| attacks | ray_attacks(square, direction, occupied))
+}
+
+/// Returns the squares a bishop on `square` attacks with the given pieces on the board
+#[inline]
+pub(crate) fn bishop_attacks(square: usize, occupied: u64) -> u64 {
+    BISHOP_RAYS.iter().fold(0, |attacks, &direction| attacks | ray_attacks(square, direction, occupied))
+}
+
+/// Iterates over the squares of a bitboard, lowest first
+pub(crate) fn squares(mut bitboard: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if bitboard == 0 {
+            return None;
+        }
+        let square = bitboard.trailing_zeros() as usize;
+        bitboard &= bitboard - 1;
+        Some(square)
+    })
+}
+
+impl Board {
+    /// Returns the squares holding `piece`, bit 0 being a1 and bit 63 h8
+    pub fn bitboard(&self, piece: Piece) -> u64 {
+        self.pieces[piece.piece_type as usize] & self.colors[piece.color as usize]
+    }
+
+    /// Returns the squares holding a piece of `color`
+    pub fn color_bitboard(&self, color: Color) -> u64 {
+        self.colors[color as usize]
+    }
+
+    /// Returns the squares holding any piece
+    pub fn occupied(&self) -> u64 {
+        self.colors[0] | self.colors[1]
+    }
+
+    /// Puts `piece` (or nothing) on `square` and returns what stood there before.
+    /// Every change to the board goes through here, so the mailbox and the bitboards agree
+    #[inline]
+    pub(crate) fn put(&mut self, square: usize, piece: Option<Piece>) -> Option<Piece> {
+        let old = std::mem::replace(&mut self.squares[square], piece);
+
+        if let Some(old) = old {
+            self.pieces[old.piece_type as usize] &= !bit(square);
+            self.colors[old.color as usize] &= !bit(square);
+        }
+        if let Some(piece) = piece {
+            self.pieces[piece.piece_type as usize] |= bit(square);
+            self.colors[piece.color as usize] |= bit(square);
+        }
+        old
+    }
+
+    /// Same as `put(square, None)`
+    #[inline]
+    pub(crate) fn take(&mut self, square: usize) -> Option<Piece> {
+        self.put(square, None)
+    }
+}
@@ -0,0 +1,129 @@
+use rand::Rng;
+
+use crate::{board_index, Board, CastlingFiles, CastlingRights, Color, Piece, PieceType};
+
+impl CastlingRights {
+    /// All four castling rights
+    pub const ALL: CastlingRights = CastlingRights {
+        white_kingside: true,
+        white_queenside: true,
+        black_kingside: true,
+        black_queenside: true,
+    };
+
+    /// Returns the rights with their fen letters in fen order
+    fn with_letters(self) -> [(bool, char); 4] {
+        [
+            (self.white_kingside, 'K'),
+            (self.white_queenside, 'Q'),
+            (self.black_kingside, 'k'),
+            (self.black_queenside, 'q'),
+        ]
+    }
+
+    /// Returns whether every right in `self` is also in `other`
+    pub fn is_subset_of(self, other: CastlingRights) -> bool {
+        self.with_letters()
+            .iter()
+            .zip(other.with_letters())
+            .all(|(&(mine, _), (theirs, _))| !mine || theirs)
+    }
+
+}
+
+impl CastlingFiles {
+    /// King on the e-file, rooks on the h- and a-file
+    pub const STANDARD: CastlingFiles = CastlingFiles { king: 4, kingside_rook: 7, queenside_rook: 0 };
+}
+
+/// Returns the rank (0..8) the pieces of `color` start on
+pub(crate) fn home_rank(color: Color) -> usize {
+    match color {
+        Color::White => 0,
+        Color::Black => 7,
+    }
+}
+
+impl Board {
+    /// Returns whether `piece` stands on `file` of the home rank of its color
+    fn is_home(&self, file: u8, piece: Piece) -> bool {
+        self.squares[board_index(file as usize, home_rank(piece.color))] == Some(piece)
+    }
+
+    /// Returns whether the king and the rook of `color` stand on their original squares
+    /// for castling with the rook on `rook_file`
+    fn can_keep_castling(&self, color: Color, rook_file: u8) -> bool {
+        let files = self.castling_files[color as usize];
+
+        self.is_home(files.king, Piece::new(PieceType::King, color))
+            && self.is_home(rook_file, Piece::new(PieceType::Rook, color))
+    }
+
+    /// Returns every castling right that the placement of the kings and rooks allows
+    pub fn supported_castling(&self) -> CastlingRights {
+        let [white, black] = self.castling_files;
+
+        CastlingRights {
+            white_kingside: self.can_keep_castling(Color::White, white.kingside_rook),
+            white_queenside: self.can_keep_castling(Color::White, white.queenside_rook),
+            black_kingside: self.can_keep_castling(Color::Black, black.kingside_rook),
+            black_queenside: self.can_keep_castling(Color::Black, black.queenside_rook),
+        }
+    }
+
+    /// Returns the castling field of a fen, e.g. "KQkq", "Kq" or "-". With `shredder`
+    /// every right is written as its rook file ("HAha"), otherwise as in X-FEN, where only
+    /// a rook with another rook of its color further out on the same side gets its file
+    pub(crate) fn castling_to_str_fen(&self, shredder: bool) -> String {
+        let mut s = String::new();
+
+        for (allowed, color, kingside) in [
+            (self.castling.white_kingside, Color::White, true),
+            (self.castling.white_queenside, Color::White, false),
+            (self.castling.black_kingside, Color::Black, true),
+            (self.castling.black_queenside, Color::Black, false),
+        ] {
+            if !allowed {continue;}
+
+            let files = self.castling_files[color as usize];
+            let rook_file = if kingside { files.kingside_rook } else { files.queenside_rook };
+            let outer_files = if kingside { rook_file + 1..8 } else { 0..rook_file };
+
+            let rook = Piece::new(PieceType::Rook, color);
+            let is_outermost = !outer_files.into_iter().any(|file| self.is_home(file, rook));
+
+            let letter = match (shredder || !is_outermost, kingside) {
+                (true, _) => (b'A' + rook_file) as char,
+                (false, true) => 'K',
+                (false, false) => 'Q',
+            };
+            s.push(match color {
+                Color::White => letter,
+                Color::Black => letter.to_ascii_lowercase(),
+            });
+        }
+
+        if s.is_empty() {
+            return "-".to_string();
+        }
+        s
+    }
+
+    /// Keeps each right from `supported_castling` with the chance `probability`
+    pub(crate) fn random_castling<R: Rng + ?Sized>(&self, rng: &mut R, probability: f64) -> CastlingRights {
+        let mut rights = self.supported_castling();
+
+        for right in [
+            &mut rights.white_kingside,
+            &mut rights.white_queenside,
+            &mut rights.black_kingside,
+            &mut rights.black_queenside,
+        ] {
+            if *right {
+                *right = rng.random_bool(probability);
+            }
+        }
+
+        rights
+    }
+}
@@ -0,0 +1,236 @@
+use rand::Rng;
+
+use crate::castling::home_rank;
+use crate::{board_index, Board, CastlingFiles, CastlingRights, Color, Piece, PieceType};
+
+/// Number of Chess960 start positions
+pub const CHESS960_POSITIONS: u16 = 960;
+
+/// The squares (0..5) of the two knights among the five files left after the bishops
+/// and the queen, in the order of the Scharnagl numbering
+const KNIGHT_PAIRS: [(usize, usize); 10] = [
+    (0, 1), (0, 2), (0, 3), (0, 4),
+    (1, 2), (1, 3), (1, 4),
+    (2, 3), (2, 4),
+    (3, 4),
+];
+
+/// Number of Transcendental Chess setups for one side: like Chess960,
+/// but the king can stand on any of the last three squares instead of between the rooks
+pub const TRANSCENDENTAL_SETUPS: u16 = 3 * CHESS960_POSITIONS;
+
+/// Returns a back rank from Scharnagl's number `n` (0..960) for the bishops, the queen
+/// and the knights, with the king on square `king` (0..3) of the three files left
+fn setup(mut n: usize, king: usize) -> [PieceType; 8] {
+    let mut rank: [Option<PieceType>; 8] = [None; 8];
+
+    // One bishop on a light square (b, d, f, h), then one on a dark square (a, c, e, g)
+    rank[2 * (n % 4) + 1] = Some(PieceType::Bishop);
+    n /= 4;
+    rank[2 * (n % 4)] = Some(PieceType::Bishop);
+    n /= 4;
+
+    let free = |rank: &[Option<PieceType>; 8]| -> Vec<usize> { (0..8).filter(|&file| rank[file].is_none()).collect() };
+
+    rank[free(&rank)[n % 6]] = Some(PieceType::Queen);
+    n /= 6;
+
+    let (first, second) = KNIGHT_PAIRS[n];
+    let files = free(&rank);
+    rank[files[first]] = Some(PieceType::Knight);
+    rank[files[second]] = Some(PieceType::Knight);
+
+    for (i, file) in free(&rank).into_iter().enumerate() {
+        rank[file] = Some(if i == king { PieceType::King } else { PieceType::Rook });
+    }
+
+    rank.map(Option::unwrap)
+}
+
+/// Returns the numbers `setup` was called with for `rank`, or None if no call gives it
+fn setup_numbers(rank: &[PieceType; 8]) -> Option<(usize, usize)> {
+    let files_of = |piece_type: PieceType| (0..8).filter(move |&file| rank[file] == piece_type);
+
+    let light_bishop = files_of(PieceType::Bishop).find(|file| file % 2 == 1)?;
+    let dark_bishop = files_of(PieceType::Bishop).find(|file| file % 2 == 0)?;
+
+    let without_bishops: Vec<usize> = (0..8).filter(|&file| rank[file] != PieceType::Bishop).collect();
+    let queen = without_bishops.iter().position(|&file| rank[file] == PieceType::Queen)?;
+
+    let rest: Vec<usize> = without_bishops.into_iter().filter(|&file| rank[file] != PieceType::Queen).collect();
+    let knights: Vec<usize> = (0..rest.len()).filter(|&i| rank[rest[i]] == PieceType::Knight).collect();
+    let knight_pair = KNIGHT_PAIRS.iter().position(|&(first, second)| knights == [first, second])?;
+
+    let last: Vec<usize> = rest.into_iter().filter(|&file| rank[file] != PieceType::Knight).collect();
+    let king = last.iter().position(|&file| rank[file] == PieceType::King)?;
+
+    let n = light_bishop / 2 + 4 * (dark_bishop / 2 + 4 * (queen + 6 * knight_pair));
+
+    // Anything else wrong (a second king, an extra queen, ...) shows up as a different rank
+    (setup(n, king) == *rank).then_some((n, king))
+}
+
+/// Returns the back rank of the Chess960 start position with Scharnagl's number `index`
+/// (0..960), from the a- to the h-file. 518 is the standard setup
+pub fn chess960_back_rank(index: u16) -> Option<[PieceType; 8]> {
+    // The king goes between the rooks
+    (index < CHESS960_POSITIONS).then(|| setup(index as usize, 1))
+}
+
+/// Returns Scharnagl's number of a back rank, or None if it is not a Chess960 setup
+pub fn chess960_index(rank: &[PieceType; 8]) -> Option<u16> {
+    match setup_numbers(rank)? {
+        (n, 1) => Some(n as u16),
+        _ => None,
+    }
+}
+
+/// Returns the Transcendental Chess back rank with number `index` (0..2880). Numbers 960
+/// to 1919 are the Chess960 setups in Scharnagl's order; below the king stands left of
+/// both rooks, above right of them
+pub fn transcendental_back_rank(index: u16) -> Option<[PieceType; 8]> {
+    let n = CHESS960_POSITIONS as usize;
+    (index < TRANSCENDENTAL_SETUPS).then(|| setup(index as usize % n, index as usize / n))
+}
+
+/// Returns the number of a Transcendental Chess back rank, see `transcendental_back_rank`
+pub fn transcendental_index(rank: &[PieceType; 8]) -> Option<u16> {
+    let (n, king) = setup_numbers(rank)?;
+    Some((n + CHESS960_POSITIONS as usize * king) as u16)
+}
+
+impl Board {
+    /// Returns the Chess960 start position with Scharnagl's number `index` (0..960),
+    /// with white to move and all castling rights, or None if the index is out of range
+    pub fn chess960(index: u16) -> Option<Board> {
+        let rank = chess960_back_rank(index)?;
+
+        let mut board = Board::new();
+        board.set_up(Color::White, &rank);
+        board.set_up(Color::Black, &rank);
+        board.castling = CastlingRights::ALL;
+
+        Some(board)
+    }
+
+    /// Returns one of the 960 Chess960 start positions, all equally likely
+    pub fn random_chess960<R: Rng + ?Sized>(rng: &mut R) -> Board {
+        Board::chess960(rng.random_range(0..CHESS960_POSITIONS)).unwrap()
+    }
+
+    /// Returns Scharnagl's number if the pieces form a Chess960 start position
+    /// (both sides with the same setup and all pawns at home).
+    /// The side to move, castling rights and clocks are not looked at
+    pub fn chess960_index(&self) -> Option<u16> {
+        match self.double_chess960_indices()? {
+            (white, black) if white == black => Some(white),
+            _ => None,
+        }
+    }
+
+    /// Returns the Double Chess960 start position where white sets up as Chess960 number
+    /// `white` and black as number `black`, with white to move and all castling rights.
+    /// Each side castles with its own rooks, so the castling field needs Shredder-FEN
+    /// (see `to_str_shredder_fen`) unless both setups happen to agree
+    pub fn double_chess960(white: u16, black: u16) -> Option<Board> {
+        let mut board = Board::new();
+        board.set_up(Color::White, &chess960_back_rank(white)?);
+        board.set_up(Color::Black, &chess960_back_rank(black)?);
+        board.castling = CastlingRights::ALL;
+
+        Some(board)
+    }
+
+    /// Returns one of the 960 x 960 Double Chess960 start positions, all equally likely
+    pub fn random_double_chess960<R: Rng + ?Sized>(rng: &mut R) -> Board {
+        let white = rng.random_range(0..CHESS960_POSITIONS);
+        let black = rng.random_range(0..CHESS960_POSITIONS);
+        Board::double_chess960(white, black).unwrap()
+    }
+
+    /// Returns the Chess960 numbers of the white and the black setup if the pieces form
+    /// a Double Chess960 start position, the inverse of `double_chess960`
+    pub fn double_chess960_indices(&self) -> Option<(u16, u16)> {
+        let (white, black) = self.home_setups()?;
+        Some((chess960_index(&white)?, chess960_index(&black)?))
+    }
+
+    /// Returns the Transcendental Chess start position with the setups numbered `white`
+    /// and `black` (0..2880, see `transcendental_back_rank`), with white to move.
+    /// The king need not stand between the rooks, so there are no castling rights
+    pub fn transcendental(white: u16, black: u16) -> Option<Board> {
+        let mut board = Board::new();
+        board.set_up(Color::White, &transcendental_back_rank(white)?);
+        board.set_up(Color::Black, &transcendental_back_rank(black)?);
+
+        Some(board)
+    }
+
+    /// Returns one of the 2880 x 2880 Transcendental Chess start positions,
+    /// all equally likely
+    pub fn random_transcendental<R: Rng + ?Sized>(rng: &mut R) -> Board {
+        let white = rng.random_range(0..TRANSCENDENTAL_SETUPS);
+        let black = rng.random_range(0..TRANSCENDENTAL_SETUPS);
+        Board::transcendental(white, black).unwrap()
+    }
+
+    /// Returns the numbers of the white and the black setup if the pieces form
+    /// a Transcendental Chess start position, the inverse of `transcendental`
+    pub fn transcendental_indices(&self) -> Option<(u16, u16)> {
+        let (white, black) = self.home_setups()?;
+        Some((transcendental_index(&white)?, transcendental_index(&black)?))
+    }
+
+    /// Returns both back ranks if every piece stands on its home rank
+    /// and all sixteen pawns stand in front of them
+    fn home_setups(&self) -> Option<([PieceType; 8], [PieceType; 8])> {
+        const HOME_RANKS: u64 = 0xFFFF_0000_0000_FFFF;
+        const WHITE_PAWNS: u64 = 0x0000_0000_0000_FF00;
+        const BLACK_PAWNS: u64 = 0x00FF_0000_0000_0000;
+
+        let pawns = |color: Color| self.bitboard(Piece::new(PieceType::Pawn, color));
+        if self.occupied() != HOME_RANKS || pawns(Color::White) != WHITE_PAWNS || pawns(Color::Black) != BLACK_PAWNS {
+            return None;
+        }
+
+        Some((self.back_rank(Color::White)?, self.back_rank(Color::Black)?))
+    }
+
+    /// Puts the pieces of `color` on their home rank as in `rank`, with the pawns in front,
+    /// and sets the castling files from the king and the rooks around it
+    pub(crate) fn set_up(&mut self, color: Color, rank: &[PieceType; 8]) {
+        let home = home_rank(color);
+        let pawns = match color {
+            Color::White => 1,
+            Color::Black => 6,
+        };
+
+        for (file, &piece_type) in rank.iter().enumerate() {
+            self.put(board_index(file, home), Some(Piece::new(piece_type, color)));
+            self.put(board_index(file, pawns), Some(Piece::new(PieceType::Pawn, color)));
+        }
+
+        // Without a rook on one side of the king (Transcendental Chess) the file does not
+        // matter, as there is no castling right for it
+        let king = rank.iter().position(|&piece_type| piece_type == PieceType::King).unwrap();
+        let rooks = || (0..8).filter(|&file| rank[file] == PieceType::Rook);
+        self.castling_files[color as usize] = CastlingFiles {
+            king: king as u8,
+            kingside_rook: rooks().rfind(|&file| file > king).unwrap_or(7) as u8,
+            queenside_rook: rooks().find(|&file| file < king).unwrap_or(0) as u8,
+        };
+    }
+
+    /// Returns the pieces on the home rank of `color` if it is full
+    fn back_rank(&self, color: Color) -> Option<[PieceType; 8]> {
+        let mut rank = [PieceType::Pawn; 8];
+        for (file, piece_type) in rank.iter_mut().enumerate() {
+            let piece = self.squares[board_index(file, home_rank(color))]?;
+            if piece.color != color {
+                return None;
+            }
+            *piece_type = piece.piece_type;
+        }
+        Some(rank)
+    }
+}
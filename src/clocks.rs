@@ -0,0 +1,111 @@
+use rand::Rng;
+
+use crate::{Board, Color, PieceType};
+
+/// How `Board::random` chooses the halfmove clock or the fullmove number
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ClockMode {
+    /// Always this value, except that the halfmove clock is lowered to the most
+    /// that fits the position (see `Random`)
+    Fixed(u32),
+    /// A random value that fits the position.
+    /// The halfmove clock is drawn from 0..=100 (but never more than the plies played so far),
+    /// the fullmove number from up to 100 moves above the smallest one that fits the material
+    Random,
+    /// The smallest value that fits the position.
+    /// The halfmove clock is 0 if the last move could have been a pawn move or a capture
+    /// and 1 otherwise, the fullmove number is the fewest moves needed to capture
+    /// and promote to reach the material on the board
+    Derived,
+}
+
+/// How many pieces of each type a side starts with, in `PieceType` order
+const START_COUNTS: [u32; 6] = [8, 2, 2, 2, 1, 1];
+
+impl Board {
+    /// Returns the fewest moves each side must have made to reach the material on the board,
+    /// indexed by `Color`. A capture takes one move of the capturing side, and every piece
+    /// above the starting count needs a promotion, which takes at least five pawn moves
+    fn min_moves_made(&self) -> [u32; 2] {
+        let counts = self.piece_counts();
+        let mut moves = [0; 2];
+
+        for color in [Color::White, Color::Black] {
+            let counts = counts[color as usize];
+            let lost = 16u32.saturating_sub(counts.iter().map(|&n| n as u32).sum());
+            let promoted: u32 = counts.iter()
+                .zip(START_COUNTS)
+                .skip(1) // pawns can not be promoted to
+                .map(|(&n, start)| (n as u32).saturating_sub(start))
+                .sum();
+
+            moves[color as usize] += 5 * promoted;
+            moves[!color as usize] += lost;
+        }
+
+        moves
+    }
+
+    /// Returns the smallest fullmove number that gives both sides enough moves
+    /// for `min_moves_made`
+    fn min_fullmove_number(&self) -> u32 {
+        let [white, black] = self.min_moves_made();
+
+        // Before white's n-th move both sides made n - 1 moves, before black's n-th move white made n
+        match self.turn {
+            Color::White => white.max(black) + 1,
+            Color::Black => white.max(black + 1),
+        }
+    }
+
+    /// Returns how many plies were played before this position given its fullmove number,
+    /// saturating at `u32::MAX`
+    fn plies_played(&self) -> u32 {
+        self.fullmove_number.saturating_sub(1)
+            .saturating_mul(2)
+            .saturating_add((self.turn == Color::Black) as u32)
+    }
+
+    /// Returns whether the last move could have reset the halfmove clock:
+    /// it was a double pawn push, the side that made it has pawns, or the side to move
+    /// is missing material that could have just been captured
+    fn last_move_could_reset_clock(&self) -> bool {
+        if self.en_passant.is_some() {
+            return true;
+        }
+
+        let counts = self.piece_counts();
+        let mover_has_pawns = counts[!self.turn as usize][PieceType::Pawn as usize] > 0;
+        let we_lost_material = counts[self.turn as usize].iter().map(|&n| n as u32).sum::<u32>() < 16;
+
+        mover_has_pawns || we_lost_material
+    }
+
+    /// Sets the fullmove number and then the halfmove clock as described by the modes
+    pub(crate) fn set_random_clocks<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        halfmove_clock: ClockMode,
+        fullmove_number: ClockMode,
+    ) {
+        let min_fullmove = self.min_fullmove_number();
+        self.fullmove_number = match fullmove_number {
+            ClockMode::Fixed(n) => n,
+            ClockMode::Random => rng.random_range(min_fullmove..=min_fullmove + 100),
+            ClockMode::Derived => min_fullmove,
+        };
+
+        // A double pawn push just reset the clock
+        let max_halfmove = match self.en_passant {
+            Some(_) => 0,
+            None => self.plies_played().min(100),
+        };
+        self.halfmove_clock = match halfmove_clock {
+            ClockMode::Fixed(n) => n.min(max_halfmove),
+            ClockMode::Random => rng.random_range(0..=max_halfmove),
+            ClockMode::Derived => {
+                if max_halfmove == 0 || self.last_move_could_reset_clock() { 0 } else { 1 }
+            },
+        };
+    }
+}
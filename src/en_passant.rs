@@ -0,0 +1,124 @@
+use rand::Rng;
+
+use crate::{board_index, board_index_reverse, Board, Color, Piece, PieceType, Square};
+
+/// When `Board::random` writes an en passant square
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum EnPassantMode {
+    /// Never, the field is always "-"
+    Never,
+    /// Whenever the last move could have been a double pawn push
+    Plausible,
+    /// Only when the side to move can actually capture en passant
+    /// (the convention of newer fen writers)
+    Legal,
+}
+
+impl Board {
+    /// Returns the en passant squares that a double pawn push of the side not to move
+    /// could have left behind. The pushed pawn must stand on its fourth rank with the two
+    /// squares behind it empty, and taking the push back must give a position where
+    /// the side to move is not in check (otherwise the push was not a legal move)
+    pub(crate) fn plausible_en_passant_squares(&self) -> Vec<usize> {
+        let mover = !self.turn;
+        let (pawn_rank, forward): (usize, isize) = match mover {
+            Color::White => (3, 1),
+            Color::Black => (4, -1),
+        };
+        let ep_rank = pawn_rank.wrapping_add_signed(-forward);
+        let origin_rank = ep_rank.wrapping_add_signed(-forward);
+
+        let Some(our_king) = self.king_square(self.turn) else {
+            return Vec::new();
+        };
+
+        let mut squares = Vec::new();
+        for file in 0..8 {
+            let pawn = board_index(file, pawn_rank);
+            let ep = board_index(file, ep_rank);
+            let origin = board_index(file, origin_rank);
+
+            if self.squares[pawn] != Some(Piece::new(PieceType::Pawn, mover)) {continue;}
+            if self.squares[ep].is_some() || self.squares[origin].is_some() {continue;}
+
+            // Take the push back and see if the side to move was left in check
+            let mut before = self.clone();
+            let pawn = before.take(pawn);
+            before.put(origin, pawn);
+            if before.is_attacked(our_king, mover) {continue;}
+
+            squares.push(ep);
+        }
+
+        squares
+    }
+
+    /// Returns whether a pawn of the side to move can capture en passant on `ep`
+    /// without leaving its own king in check
+    pub(crate) fn can_capture_en_passant(&self, ep: usize) -> bool {
+        let (ep_file, ep_rank) = board_index_reverse(ep);
+        let pawn_rank = match self.turn {
+            Color::White => ep_rank - 1,
+            Color::Black => ep_rank + 1,
+        };
+        let captured = board_index(ep_file, pawn_rank);
+
+        let Some(our_king) = self.king_square(self.turn) else {
+            return false;
+        };
+
+        for file in [ep_file.wrapping_sub(1), ep_file + 1] {
+            if file >= 8 {continue;}
+
+            let from = board_index(file, pawn_rank);
+            if self.squares[from] != Some(Piece::new(PieceType::Pawn, self.turn)) {continue;}
+
+            let mut after = self.clone();
+            let pawn = after.take(from);
+            after.put(ep, pawn);
+            after.take(captured);
+            if !after.is_attacked(our_king, !self.turn) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns the en passant squares that `mode` allows in this position, the ones
+    /// `Board::random` picks from. The en passant square of the board is not looked at
+    pub fn en_passant_squares(&self, mode: EnPassantMode) -> Vec<Square> {
+        self.en_passant_indices(mode)
+            .into_iter()
+            .map(|ep| Square(ep as u8))
+            .collect()
+    }
+
+    /// Same as `en_passant_squares`, as square numbers
+    fn en_passant_indices(&self, mode: EnPassantMode) -> Vec<usize> {
+        let mut squares = match mode {
+            EnPassantMode::Never => return Vec::new(),
+            EnPassantMode::Plausible | EnPassantMode::Legal => self.plausible_en_passant_squares(),
+        };
+        if mode == EnPassantMode::Legal {
+            squares.retain(|&ep| self.can_capture_en_passant(ep));
+        }
+        squares
+    }
+
+    /// Picks an en passant square for `mode` with the chance `probability`,
+    /// if the position has any
+    pub(crate) fn random_en_passant<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        mode: EnPassantMode,
+        probability: f64,
+    ) -> Option<usize> {
+        let squares = self.en_passant_indices(mode);
+
+        if squares.is_empty() || !rng.random_bool(probability) {
+            return None;
+        }
+        Some(squares[rng.random_range(0..squares.len())])
+    }
+}
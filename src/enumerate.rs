@@ -0,0 +1,153 @@
+use crate::{board_index, board_index_reverse, Board, Color, Material, Piece, PieceType, N_SQUARES};
+
+/// Iterator over every legal position with some exact material, for both sides to move.
+/// Positions come without castling rights or en passant square.
+///
+/// The number of placements grows about 64 times with every extra piece,
+/// so this is only practical for a handful of pieces
+pub struct Positions {
+    pieces: Vec<Piece>,
+    /// Squares of the pieces placed so far, in the order of `pieces`
+    squares: Vec<usize>,
+    started: bool,
+    dedup_symmetric: bool,
+    /// Legal boards of the current placement that were not returned yet
+    pending: Vec<Board>,
+}
+
+/// Takes (file, rank) and returns the (file, rank) it is mapped to
+type Symmetry = fn(usize, usize) -> (usize, usize);
+
+/// The ways to map the board onto itself: mirrors and rotations
+const SYMMETRIES: [Symmetry; 8] = [
+    |file, rank| (file, rank),
+    |file, rank| (7 - file, rank),
+    |file, rank| (file, 7 - rank),
+    |file, rank| (7 - file, 7 - rank),
+    |file, rank| (rank, file),
+    |file, rank| (7 - rank, file),
+    |file, rank| (rank, 7 - file),
+    |file, rank| (7 - rank, 7 - file),
+];
+
+impl Positions {
+    /// Creates the iterator. With `dedup_symmetric`, positions that are a mirror image or
+    /// rotation of another are only returned once. Without pawns that covers all eight
+    /// symmetries of the board, with pawns only the mirror between the a- and h-file
+    pub fn new(material: &Material, dedup_symmetric: bool) -> Self {
+        Positions {
+            pieces: material.pieces(),
+            squares: Vec::new(),
+            started: false,
+            dedup_symmetric,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Returns the first square from `from` on that piece `i` can stand on, given the
+    /// pieces before it. Pawns stay off the first and last rank
+    fn free_square(&self, i: usize, from: usize) -> Option<usize> {
+        (from..N_SQUARES).find(|&square| {
+            let (_, rank) = board_index_reverse(square);
+            let is_pawn = self.pieces[i].piece_type == PieceType::Pawn;
+
+            !(is_pawn && (rank == 0 || rank == 7)) && !self.squares[..i].contains(&square)
+        })
+    }
+
+    /// Returns the lowest square piece `i` may start from. Identical pieces are placed in
+    /// increasing order, so every set of squares is only visited once
+    fn first_square(&self, i: usize) -> usize {
+        if i > 0 && self.pieces[i] == self.pieces[i - 1] {
+            return self.squares[i - 1] + 1;
+        }
+        0
+    }
+
+    /// Moves on to the next placement, returns false when there is none
+    fn next_placement(&mut self) -> bool {
+        let n = self.pieces.len();
+
+        let (mut i, mut from) = if self.started {
+            match self.squares.pop() {
+                Some(square) => (n - 1, square + 1),
+                None => return false,
+            }
+        } else {
+            self.started = true;
+            (0, 0)
+        };
+
+        loop {
+            match self.free_square(i, from) {
+                Some(square) => {
+                    self.squares.push(square);
+                    if self.squares.len() == n {
+                        return true;
+                    }
+                    i += 1;
+                    from = self.first_square(i);
+                },
+                None => {
+                    if i == 0 {
+                        return false;
+                    }
+                    i -= 1;
+                    from = self.squares.pop().unwrap() + 1;
+                },
+            }
+        }
+    }
+
+    /// Returns whether no mirror image or rotation of the board is smaller,
+    /// comparing the squares from a1 to h8
+    fn is_canonical(&self, board: &Board) -> bool {
+        let code = |piece: &Option<Piece>| match piece {
+            Some(piece) => 1 + piece.color as u8 * 6 + piece.piece_type as u8,
+            None => 0,
+        };
+        let original: Vec<u8> = board.squares.iter().map(code).collect();
+
+        let has_pawns = self.pieces.iter().any(|piece| piece.piece_type == PieceType::Pawn);
+        let symmetries = if has_pawns { &SYMMETRIES[..2] } else { &SYMMETRIES[..] };
+
+        symmetries.iter().all(|symmetry| {
+            let mut transformed = [0; N_SQUARES];
+            for (index, &piece) in original.iter().enumerate() {
+                let (file, rank) = board_index_reverse(index);
+                let (file, rank) = symmetry(file, rank);
+                transformed[board_index(file, rank)] = piece;
+            }
+            transformed[..] >= original[..]
+        })
+    }
+}
+
+impl Iterator for Positions {
+    type Item = Board;
+
+    fn next(&mut self) -> Option<Board> {
+        loop {
+            if let Some(board) = self.pending.pop() {
+                return Some(board);
+            }
+            if !self.next_placement() {
+                return None;
+            }
+
+            let mut board = Board::new();
+            for (&piece, &square) in self.pieces.iter().zip(&self.squares) {
+                board.put(square, Some(piece));
+            }
+            if self.dedup_symmetric && !self.is_canonical(&board) {continue;}
+
+            // Pushed in reverse, so white to move comes out first
+            for turn in [Color::Black, Color::White] {
+                board.turn = turn;
+                if board.is_legal() {
+                    self.pending.push(board.clone());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use rand::Rng;
+
+use crate::{board_index, Board, Color, MaterialSpec, Piece, PieceCount, PieceType, SideMaterial, N_SQUARES, PAWN_SQUARES};
+
+/// z value of a two-sided 95% confidence interval
+const Z_95: f64 = 1.959964;
+
+/// The non-pawn piece types, in the order of `SideConfig::pieces`
+const PIECES: [PieceType; 5] = [
+    PieceType::King,
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+];
+
+/// Exact material of one side
+#[derive(Clone)]
+struct SideConfig {
+    pawns: usize,
+    /// Counts in the order of `PIECES`
+    pieces: [usize; 5],
+}
+
+/// All material of one side with the same number of pawns and other pieces.
+/// Placements only depend on these two numbers and the product of the factorials of the
+/// counts, so every config is weighted by 1 / (k_1! k_2! ...)
+struct Bucket {
+    configs: Vec<SideConfig>,
+    /// Running sum of the config weights, for sampling
+    cumulative: Vec<f64>,
+}
+
+/// A set of placements to sample from: every placement (with either side to move) of every
+/// material in the set, pawns only from the second to the seventh rank
+pub struct SampleSpace {
+    /// Per color, buckets indexed by [pawns][other pieces]
+    buckets: [Vec<Vec<Bucket>>; 2],
+    /// (white pawns, white pieces, black pawns, black pieces) with a positive number of placements
+    combinations: Vec<(usize, usize, usize, usize)>,
+    /// Running sum of the placements of `combinations`
+    cumulative: Vec<f64>,
+}
+
+/// The result of `estimate`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Estimate {
+    pub samples: u64,
+    pub legal: u64,
+    /// How many placements the sample space has
+    pub space_size: f64,
+}
+
+fn factorial(n: usize) -> f64 {
+    (1..=n).map(|i| i as f64).product()
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    factorial(n) / (factorial(k) * factorial(n - k))
+}
+
+impl SideConfig {
+    fn weight(&self) -> f64 {
+        1.0 / self.pieces.iter().map(|&k| factorial(k)).product::<f64>()
+    }
+}
+
+impl SampleSpace {
+    /// Every material a side can have in a game by counting alone: at most eight pawns,
+    /// and no more promoted pieces than missing pawns. This is a much looser bound than
+    /// reachability from the start position, so the estimate is larger than the number of
+    /// positions that can occur in a game
+    pub fn all() -> Self {
+        let mut configs = Vec::new();
+
+        for pawns in 0..=8usize {
+            let promotions = 8 - pawns;
+            for queens in 0..=1 + promotions {
+                for rooks in 0..=2 + promotions {
+                    for bishops in 0..=2 + promotions {
+                        for knights in 0..=2 + promotions {
+                            let promoted = queens.saturating_sub(1)
+                                + rooks.saturating_sub(2)
+                                + bishops.saturating_sub(2)
+                                + knights.saturating_sub(2);
+                            if promoted > promotions {continue;}
+
+                            configs.push(SideConfig { pawns, pieces: [1, queens, rooks, bishops, knights] });
+                        }
+                    }
+                }
+            }
+        }
+
+        SampleSpace::new([configs.clone(), configs])
+    }
+
+    /// Every exact material within the ranges of `spec`
+    pub fn from_spec(spec: &MaterialSpec) -> Self {
+        SampleSpace::new([side_configs(&spec.white), side_configs(&spec.black)])
+    }
+
+    fn new(configs: [Vec<SideConfig>; 2]) -> Self {
+        let [white, black] = configs.map(|configs| {
+            let mut buckets: Vec<Vec<Bucket>> = (0..=PAWN_SQUARES)
+                .map(|_| (0..=N_SQUARES).map(|_| Bucket { configs: Vec::new(), cumulative: Vec::new() }).collect())
+                .collect();
+
+            for config in configs {
+                let bucket = &mut buckets[config.pawns][config.pieces.iter().sum::<usize>()];
+                let total = bucket.cumulative.last().copied().unwrap_or(0.0);
+                bucket.cumulative.push(total + config.weight());
+                bucket.configs.push(config);
+            }
+            buckets
+        });
+
+        let weight = |buckets: &Vec<Vec<Bucket>>, pawns: usize, pieces: usize| {
+            buckets[pawns][pieces].cumulative.last().copied().unwrap_or(0.0)
+        };
+
+        let mut combinations = Vec::new();
+        let mut cumulative = Vec::new();
+        let mut total = 0.0;
+        for white_pawns in 0..=PAWN_SQUARES {
+            for black_pawns in 0..=PAWN_SQUARES - white_pawns {
+                let pawn_placements = binomial(PAWN_SQUARES, white_pawns) * binomial(PAWN_SQUARES - white_pawns, black_pawns);
+                let free = N_SQUARES - white_pawns - black_pawns;
+
+                for white_pieces in 0..=free {
+                    let white_weight = weight(&white, white_pawns, white_pieces);
+                    if white_weight == 0.0 {continue;}
+
+                    for black_pieces in 0..=free - white_pieces {
+                        let black_weight = weight(&black, black_pawns, black_pieces);
+                        if black_weight == 0.0 {continue;}
+
+                        // Both sides to move, the pawns, then the other pieces as a multinomial
+                        let placements = 2.0 * pawn_placements
+                            * factorial(free) / factorial(free - white_pieces - black_pieces)
+                            * white_weight * black_weight;
+
+                        total += placements;
+                        combinations.push((white_pawns, white_pieces, black_pawns, black_pieces));
+                        cumulative.push(total);
+                    }
+                }
+            }
+        }
+
+        SampleSpace { buckets: [white, black], combinations, cumulative }
+    }
+
+    /// Returns the number of placements in the space
+    pub fn size(&self) -> f64 {
+        self.cumulative.last().copied().unwrap_or(0.0)
+    }
+
+    /// Returns a placement from the space, every placement being equally likely.
+    /// The board can be illegal. Panics if the space is empty
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Board {
+        let (white_pawns, white_pieces, black_pawns, black_pieces) =
+            self.combinations[pick(rng, &self.cumulative)];
+
+        let mut board = Board::new();
+        board.turn = if rng.random_bool(0.5) { Color::White } else { Color::Black };
+
+        // Pawns go to random squares of the second to seventh rank, the rest to random free squares
+        let mut free: Vec<usize> = (board_index(0, 1)..board_index(0, 7)).collect();
+        for (color, pawns) in [(Color::White, white_pawns), (Color::Black, black_pawns)] {
+            for _ in 0..pawns {
+                let square = free.swap_remove(rng.random_range(0..free.len()));
+                board.put(square, Some(Piece::new(PieceType::Pawn, color)));
+            }
+        }
+
+        let mut free: Vec<usize> = (0..N_SQUARES).filter(|&square| board.squares[square].is_none()).collect();
+        for (color, pawns, pieces) in [(Color::White, white_pawns, white_pieces), (Color::Black, black_pawns, black_pieces)] {
+            let bucket = &self.buckets[color as usize][pawns][pieces];
+            let config = &bucket.configs[pick(rng, &bucket.cumulative)];
+
+            for (piece_type, &count) in PIECES.iter().zip(&config.pieces) {
+                for _ in 0..count {
+                    let square = free.swap_remove(rng.random_range(0..free.len()));
+                    board.put(square, Some(Piece::new(*piece_type, color)));
+                }
+            }
+        }
+
+        board
+    }
+}
+
+/// Returns every exact material of one side within the ranges
+fn side_configs(material: &SideMaterial) -> Vec<SideConfig> {
+    let range = |count: PieceCount| count.min as usize..=count.max as usize;
+
+    let mut configs = Vec::new();
+    for pawns in range(material.pawns) {
+        for queens in range(material.queens) {
+            for rooks in range(material.rooks) {
+                for bishops in range(material.bishops) {
+                    for knights in range(material.knights) {
+                        configs.push(SideConfig { pawns, pieces: [1, queens, rooks, bishops, knights] });
+                    }
+                }
+            }
+        }
+    }
+    configs
+}
+
+/// Returns an index into `cumulative` with chance proportional to the step at that index
+fn pick<R: Rng + ?Sized>(rng: &mut R, cumulative: &[f64]) -> usize {
+    let target = rng.random::<f64>() * cumulative.last().unwrap();
+    cumulative.partition_point(|&sum| sum <= target).min(cumulative.len() - 1)
+}
+
+impl Estimate {
+    /// Returns the share of legal samples
+    pub fn legal_ratio(&self) -> f64 {
+        self.legal as f64 / self.samples as f64
+    }
+
+    /// Returns the estimated number of legal positions: the share of legal samples
+    /// times the size of the sample space
+    pub fn count(&self) -> f64 {
+        self.legal_ratio() * self.space_size
+    }
+
+    /// Returns a 95% confidence interval for the number of legal positions,
+    /// from the Wilson score interval of the legal share
+    pub fn interval(&self) -> (f64, f64) {
+        let n = self.samples as f64;
+        let p = self.legal_ratio();
+        let z2 = Z_95 * Z_95;
+
+        let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let margin = Z_95 / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+
+        ((center - margin).max(0.0) * self.space_size, (center + margin).min(1.0) * self.space_size)
+    }
+}
+
+/// Estimates how many legal positions `space` has by testing `samples` uniform placements
+/// with `Board::is_legal`. Panics if `samples` is 0, as there would be no share to scale
+pub fn estimate<R: Rng + ?Sized>(rng: &mut R, space: &SampleSpace, samples: u64) -> Estimate {
+    assert!(samples > 0, "an estimate needs at least one sample");
+
+    let legal = (0..samples).filter(|_| space.sample(rng).is_legal()).count() as u64;
+
+    Estimate { samples, legal, space_size: space.size() }
+}
@@ -0,0 +1,322 @@
+use std::fmt;
+
+use crate::castling::home_rank;
+use crate::{board_index, Board, CastlingRights, Color, Piece, PieceType};
+
+/// The six space separated fields of a fen
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FenField {
+    Placement,
+    SideToMove,
+    Castling,
+    EnPassant,
+    HalfmoveClock,
+    FullmoveNumber,
+}
+
+/// What exactly is wrong with a fen field
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FenErrorKind {
+    /// The fen ended before this field
+    MissingField,
+    /// There is more text after the fullmove number
+    TrailingText,
+    /// A character that is not allowed at this position
+    UnexpectedChar(char),
+    /// A rank of the placement that does not describe exactly 8 squares
+    BadRankLength,
+    /// A placement that does not have exactly 8 ranks
+    BadRankCount,
+    /// The same castling right is given twice
+    DuplicateCastling(char),
+    /// An en passant square that is not on the third or sixth rank
+    BadEnPassantRank,
+    /// A clock that is not a number or does not fit
+    BadNumber,
+}
+
+/// An error from `Board::from_fen`: which field failed, at which character offset
+/// of the input, and why
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FenError {
+    pub field: FenField,
+    pub offset: usize,
+    pub kind: FenErrorKind,
+}
+
+impl fmt::Display for FenField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FenField::Placement => "piece placement",
+            FenField::SideToMove => "side to move",
+            FenField::Castling => "castling rights",
+            FenField::EnPassant => "en passant square",
+            FenField::HalfmoveClock => "halfmove clock",
+            FenField::FullmoveNumber => "fullmove number",
+        };
+        f.write_str(name)
+    }
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid fen {} at offset {}: ", self.field, self.offset)?;
+
+        match &self.kind {
+            FenErrorKind::MissingField => write!(f, "field is missing"),
+            FenErrorKind::TrailingText => write!(f, "unexpected text after the last field"),
+            FenErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            FenErrorKind::BadRankLength => write!(f, "rank does not have 8 squares"),
+            FenErrorKind::BadRankCount => write!(f, "placement does not have 8 ranks"),
+            FenErrorKind::DuplicateCastling(c) => write!(f, "castling right {:?} is given twice", c),
+            FenErrorKind::BadEnPassantRank => write!(f, "en passant square must be on rank 3 or 6"),
+            FenErrorKind::BadNumber => write!(f, "not a valid number"),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+/// Splits a fen into its space separated fields, keeping the offset where each one starts
+struct Fields<'a> {
+    fen: &'a str,
+    offset: usize,
+}
+
+impl<'a> Fields<'a> {
+    /// Returns the next field and its offset, or a MissingField error for `field`
+    fn next(&mut self, field: FenField) -> Result<(&'a str, usize), FenError> {
+        let rest = &self.fen[self.offset..];
+        let start = self.offset + (rest.len() - rest.trim_start_matches(' ').len());
+        let len = self.fen[start..].find(' ').unwrap_or(self.fen.len() - start);
+
+        if len == 0 {
+            return Err(FenError { field, offset: start, kind: FenErrorKind::MissingField });
+        }
+        self.offset = start + len;
+
+        Ok((&self.fen[start..start + len], start))
+    }
+
+    /// Returns an error if anything but spaces is left
+    fn finish(&self) -> Result<(), FenError> {
+        let rest = &self.fen[self.offset..];
+        let trimmed = rest.trim_start_matches(' ');
+
+        if trimmed.is_empty() {
+            return Ok(());
+        }
+        Err(FenError {
+            field: FenField::FullmoveNumber,
+            offset: self.offset + (rest.len() - trimmed.len()),
+            kind: FenErrorKind::TrailingText,
+        })
+    }
+}
+
+impl Board {
+    /// Parses a board from all six fields of a fen.
+    /// For any fen written by `to_str_fen`, `Board::from_fen(fen)?.to_str_fen()` gives back the same string.
+    ///
+    /// Only the syntax is checked here, the position itself can still be illegal
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        let mut board = Board::new();
+        let mut fields = Fields { fen, offset: 0 };
+
+        let (placement, offset) = fields.next(FenField::Placement)?;
+        board.parse_placement(placement, offset)?;
+
+        let (turn, offset) = fields.next(FenField::SideToMove)?;
+        board.turn = match turn {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return Err(unexpected_char(FenField::SideToMove, turn, offset, 0)),
+        };
+
+        let (castling, offset) = fields.next(FenField::Castling)?;
+        board.set_castling_from_fen(&parse_castling(castling, offset)?)?;
+
+        let (en_passant, offset) = fields.next(FenField::EnPassant)?;
+        board.en_passant = parse_en_passant(en_passant, offset)?;
+
+        let (halfmove_clock, offset) = fields.next(FenField::HalfmoveClock)?;
+        board.halfmove_clock = parse_number(halfmove_clock, offset, FenField::HalfmoveClock)?;
+
+        let (fullmove_number, offset) = fields.next(FenField::FullmoveNumber)?;
+        board.fullmove_number = parse_number(fullmove_number, offset, FenField::FullmoveNumber)?;
+        if board.fullmove_number == 0 {
+            return Err(FenError { field: FenField::FullmoveNumber, offset, kind: FenErrorKind::BadNumber });
+        }
+
+        fields.finish()?;
+
+        Ok(board)
+    }
+
+    /// Sets the castling rights and files from the letters of the castling field.
+    /// "K" and "Q" stand for the outermost rook on that side of the king (as in X-FEN),
+    /// a file letter for the rook on that file (as in Shredder-FEN). When the king or the
+    /// rook is missing the standard files are kept, and `is_legal` refuses the right.
+    /// A rook file that is the file of the king is refused here
+    fn set_castling_from_fen(&mut self, letters: &[(char, usize)]) -> Result<(), FenError> {
+        let mut rights = CastlingRights::default();
+
+        for &(c, offset) in letters {
+            let color = if c.is_ascii_uppercase() { Color::White } else { Color::Black };
+            let home: [Option<Piece>; 8] = std::array::from_fn(|file| self.squares[board_index(file, home_rank(color))]);
+            let files_with = move |piece_type: PieceType| {
+                (0..8u8).filter(move |&file| home[file as usize] == Some(Piece::new(piece_type, color)))
+            };
+
+            let king = files_with(PieceType::King).next().unwrap_or(4);
+            let rook_file = match c.to_ascii_uppercase() {
+                'K' => files_with(PieceType::Rook).rfind(|&file| file > king).unwrap_or(7),
+                'Q' => files_with(PieceType::Rook).find(|&file| file < king).unwrap_or(0),
+                letter => letter as u8 - b'A',
+            };
+            if rook_file == king {
+                return Err(FenError { field: FenField::Castling, offset, kind: FenErrorKind::UnexpectedChar(c) });
+            }
+            let kingside = rook_file > king;
+
+            let right = match (color, kingside) {
+                (Color::White, true) => &mut rights.white_kingside,
+                (Color::White, false) => &mut rights.white_queenside,
+                (Color::Black, true) => &mut rights.black_kingside,
+                (Color::Black, false) => &mut rights.black_queenside,
+            };
+            if *right {
+                return Err(FenError { field: FenField::Castling, offset, kind: FenErrorKind::DuplicateCastling(c) });
+            }
+            *right = true;
+
+            let files = &mut self.castling_files[color as usize];
+            files.king = king;
+            if kingside {
+                files.kingside_rook = rook_file;
+            } else {
+                files.queenside_rook = rook_file;
+            }
+        }
+
+        self.castling = rights;
+        Ok(())
+    }
+
+    /// Fills the squares from the placement field, which starts at `offset` in the fen
+    fn parse_placement(&mut self, placement: &str, offset: usize) -> Result<(), FenError> {
+        let error = |offset: usize, kind: FenErrorKind| FenError { field: FenField::Placement, offset, kind };
+
+        let mut rank: usize = 7;
+        let mut file: usize = 0;
+        let mut after_digit = false;
+
+        for (i, c) in placement.char_indices() {
+            match c {
+                '/' => {
+                    if file != 8 {
+                        return Err(error(offset + i, FenErrorKind::BadRankLength));
+                    }
+                    if rank == 0 {
+                        return Err(error(offset + i, FenErrorKind::BadRankCount));
+                    }
+                    rank -= 1;
+                    file = 0;
+                },
+                '1'..='8' => {
+                    // Neighbouring empty squares are always written as one digit
+                    if after_digit {
+                        return Err(error(offset + i, FenErrorKind::UnexpectedChar(c)));
+                    }
+                    file += c as usize - '0' as usize;
+                    if file > 8 {
+                        return Err(error(offset + i, FenErrorKind::BadRankLength));
+                    }
+                },
+                _ => {
+                    let piece = c.is_ascii()
+                        .then(|| Piece::from_char(c as u8))
+                        .flatten()
+                        .ok_or(error(offset + i, FenErrorKind::UnexpectedChar(c)))?;
+                    if file == 8 {
+                        return Err(error(offset + i, FenErrorKind::BadRankLength));
+                    }
+
+                    self.put(board_index(file, rank), Some(piece));
+                    file += 1;
+                },
+            }
+            after_digit = c.is_ascii_digit();
+        }
+
+        let end = offset + placement.len();
+        if file != 8 {
+            return Err(error(end, FenErrorKind::BadRankLength));
+        }
+        if rank != 0 {
+            return Err(error(end, FenErrorKind::BadRankCount));
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds an UnexpectedChar error for the character at byte `i` of `text`
+fn unexpected_char(field: FenField, text: &str, offset: usize, i: usize) -> FenError {
+    FenError {
+        field,
+        offset: offset + i,
+        kind: FenErrorKind::UnexpectedChar(text[i..].chars().next().unwrap()),
+    }
+}
+
+/// Returns the letters of the castling field with their offsets in the fen.
+/// Which rooks they stand for depends on the placement, see `Board::set_castling_from_fen`
+fn parse_castling(castling: &str, offset: usize) -> Result<Vec<(char, usize)>, FenError> {
+    if castling == "-" {
+        return Ok(Vec::new());
+    }
+
+    castling.char_indices()
+        .map(|(i, c)| match c {
+            'K' | 'Q' | 'k' | 'q' | 'A'..='H' | 'a'..='h' => Ok((c, offset + i)),
+            _ => Err(unexpected_char(FenField::Castling, castling, offset, i)),
+        })
+        .collect()
+}
+
+fn parse_en_passant(en_passant: &str, offset: usize) -> Result<Option<usize>, FenError> {
+    if en_passant == "-" {
+        return Ok(None);
+    }
+
+    let bytes = en_passant.as_bytes();
+    if !(b'a'..=b'h').contains(&bytes[0]) {
+        return Err(unexpected_char(FenField::EnPassant, en_passant, offset, 0));
+    }
+    if bytes.len() < 2 || !(b'1'..=b'8').contains(&bytes[1]) {
+        return Err(match bytes.len() {
+            1 => FenError { field: FenField::EnPassant, offset: offset + 1, kind: FenErrorKind::MissingField },
+            _ => unexpected_char(FenField::EnPassant, en_passant, offset, 1),
+        });
+    }
+    if bytes.len() > 2 {
+        return Err(unexpected_char(FenField::EnPassant, en_passant, offset, 2));
+    }
+
+    let file = (bytes[0] - b'a') as usize;
+    let rank = (bytes[1] - b'1') as usize;
+    if rank != 2 && rank != 5 {
+        return Err(FenError { field: FenField::EnPassant, offset: offset + 1, kind: FenErrorKind::BadEnPassantRank });
+    }
+
+    Ok(Some(board_index(file, rank)))
+}
+
+fn parse_number(number: &str, offset: usize, field: FenField) -> Result<u32, FenError> {
+    if let Some(i) = number.find(|c: char| !c.is_ascii_digit()) {
+        return Err(unexpected_char(field, number, offset, i));
+    }
+
+    number.parse().map_err(|_| FenError { field, offset, kind: FenErrorKind::BadNumber })
+}
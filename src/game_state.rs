@@ -0,0 +1,33 @@
+use crate::Board;
+
+/// What the side to move is facing. Every position is in exactly one state
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GameState {
+    /// In check without a legal move
+    Checkmate,
+    /// Not in check, but without a legal move
+    Stalemate,
+    /// Checked by two pieces at once, with a legal move
+    DoubleCheck,
+    /// Checked by exactly one piece, with a legal move. Double checks are `DoubleCheck`,
+    /// so `Board::is_check` is the test for any check
+    Check,
+    /// Not in check and with a legal move
+    Quiet,
+}
+
+impl Board {
+    /// Classifies the position from the point of view of the side to move
+    pub fn game_state(&self) -> GameState {
+        let checkers = self.checkers().len();
+        let has_moves = !self.legal_moves().is_empty();
+
+        match (checkers, has_moves) {
+            (0, false) => GameState::Stalemate,
+            (_, false) => GameState::Checkmate,
+            (0, true) => GameState::Quiet,
+            (1, true) => GameState::Check,
+            (_, true) => GameState::DoubleCheck,
+        }
+    }
+}
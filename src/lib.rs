@@ -1,13 +1,70 @@
 #![allow(unused)]
-use rand::Rng;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Not;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+mod attacks;
+mod bitboard;
+mod castling;
+mod chess960;
+mod clocks;
+mod en_passant;
+mod enumerate;
+mod estimate;
+mod fen;
+mod game_state;
+mod material;
+mod movegen;
+mod odds;
+mod perft;
+mod playout;
+mod polyglot;
+mod profile;
+mod ranking;
+mod reachability;
+mod retro;
+mod zobrist;
+
+pub use chess960::{
+    chess960_back_rank, chess960_index, transcendental_back_rank, transcendental_index, CHESS960_POSITIONS,
+    TRANSCENDENTAL_SETUPS,
+};
+pub use clocks::ClockMode;
+pub use en_passant::EnPassantMode;
+pub use enumerate::Positions;
+pub use estimate::{estimate, Estimate, SampleSpace};
+pub use fen::{FenError, FenErrorKind, FenField};
+pub use game_state::GameState;
+pub use material::{Material, MaterialError, MaterialErrorKind, MaterialSpec};
+pub use movegen::Move;
+pub use odds::Odds;
+pub use perft::{divide, perft};
+pub use playout::{MoveWeights, PlayoutConfig};
+pub use profile::{Profile, UnknownProfile};
+pub use ranking::PositionIndex;
+pub use reachability::Unreachable;
+pub use retro::{RetroError, UnMove};
+pub use zobrist::{Dedup, UniqueGenerator};
 
 const N_SQUARES: usize = 64;
 
-type Color = bool;
-const WHITE: Color = true;
-const BLACK: Color = false;
+/// Squares a pawn can stand on, a2 to h7
+pub(crate) const PAWN_SQUARES: usize = 6 * 8;
 
-enum PieceType {
+/// The standard start position
+pub const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Color {
+    White,
+    Black,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PieceType {
     Pawn,
     Rook,
     Knight,
@@ -16,16 +73,106 @@ enum PieceType {
     King,
 }
 
-struct Piece {
-    piece_type: PieceType,
-    color: Color,
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Piece {
+    pub piece_type: PieceType,
+    pub color: Color,
+}
+
+/// One of the 64 squares, a1 = 0, b1 = 1, ..., h8 = 63
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Square(u8);
+
+/// Which castling moves are still allowed
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+pub struct CastlingRights {
+    pub white_kingside: bool,
+    pub white_queenside: bool,
+    pub black_kingside: bool,
+    pub black_queenside: bool,
+}
+
+/// The files the king and the rooks of one color castle from. Standard chess uses the
+/// e-, h- and a-file; in Chess960 they depend on the start position
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct CastlingFiles {
+    pub king: u8,
+    pub kingside_rook: u8,
+    pub queenside_rook: u8,
 }
 
-struct Board {
+/// Two boards are equal when they give the same fen: the castling files of a side only
+/// count as far as its castling rights use them
+#[derive(Clone, Debug)]
+pub struct Board {
+    /// The mailbox view: what stands on each square
     squares: [Option<Piece>; N_SQUARES],
+    /// The bitboard view, indexed by `PieceType` and by `Color`.
+    /// Kept in step with `squares` by `put`
+    pieces: [u64; 6],
+    colors: [u64; 2],
     turn: Color,
+    castling: CastlingRights,
+    /// Indexed by `Color`
+    castling_files: [CastlingFiles; 2],
+    /// The square behind a pawn that just moved two squares
+    en_passant: Option<usize>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+}
+
+/// Inclusive range for how many pieces of one type a side gets
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct PieceCount {
+    pub min: u8,
+    pub max: u8,
 }
 
+/// Material of one side apart from the king, which is always placed
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct SideMaterial {
+    pub pawns: PieceCount,
+    pub knights: PieceCount,
+    pub bishops: PieceCount,
+    pub rooks: PieceCount,
+    pub queens: PieceCount,
+}
+
+/// Settings for `Board::random`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RandomConfig {
+    pub white: SideMaterial,
+    pub black: SideMaterial,
+    /// Chance (0.0..=1.0) to keep each castling right that the placement allows,
+    /// i.e. where the king and the rook stand on their original squares
+    pub castling_probability: f64,
+    /// Whether to write an en passant square when the position allows one
+    pub en_passant: EnPassantMode,
+    /// Chance (0.0..=1.0) to write an en passant square when `en_passant` allows one
+    pub en_passant_probability: f64,
+    pub halfmove_clock: ClockMode,
+    pub fullmove_number: ClockMode,
+    /// Only return positions in this state, resampling until one is found.
+    /// `GameState::Check` does not match double checks
+    pub target_state: Option<GameState>,
+    /// How many placements to try before giving up, counting illegal ones too
+    pub max_attempts: u32,
+    /// Only return positions that pass `Board::check_reachable`, resampling the others
+    pub reachable: bool,
+    /// Only return positions with a legal last move (see `Board::check_last_move`),
+    /// resampling the others
+    pub last_move: bool,
+    /// Places the pieces with the tendencies of this profile instead of uniformly.
+    /// The material still comes from `white` and `black`
+    pub profile: Option<Profile>,
+}
+
+/// Why `Board::try_random` could not produce a board
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GenerateError {
+    /// None of the placements tried was legal and matched the config
+    AttemptsExhausted { attempts: u32 },
+}
 
 
 #[inline]
@@ -48,91 +195,392 @@ fn board_index_reverse(index: usize) -> (usize, usize)  {
     (index % 8, index / 8)
 }
 
+impl Color {
+    /// Returns the other color
+    pub fn opposite(self) -> Self {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
+
+impl Not for Color {
+    type Output = Color;
+
+    fn not(self) -> Color {
+        self.opposite()
+    }
+}
+
+impl Square {
+    /// Takes a file and a rank (0..8) and returns the square, or None if they are out of bounds
+    pub fn new(file: u8, rank: u8) -> Option<Self> {
+        if file >= 8 || rank >= 8 {
+            return None;
+        }
+        Some(Square(8 * rank + file))
+    }
+
+    /// Takes an index (0..64) and returns the square, or None if it is out of bounds
+    pub fn from_index(index: u8) -> Option<Self> {
+        if index as usize >= N_SQUARES {
+            return None;
+        }
+        Some(Square(index))
+    }
+
+    /// Parses an algebraic square name such as "e4"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.as_bytes() {
+            &[file @ b'a'..=b'h', rank @ b'1'..=b'8'] => Square::new(file - b'a', rank - b'1'),
+            _ => None,
+        }
+    }
+
+    /// Returns the index (0..64) of the square
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Returns the file (0..8), 0 being the a-file
+    pub fn file(self) -> u8 {
+        self.0 % 8
+    }
+
+    /// Returns the rank (0..8), 0 being the first rank
+    pub fn rank(self) -> u8 {
+        self.0 / 8
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", (b'a' + self.file()) as char, self.rank() + 1)
+    }
+}
+
+
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.squares == other.squares
+            && self.turn == other.turn
+            && self.castling == other.castling
+            && self.used_castling_files() == other.used_castling_files()
+            && self.en_passant == other.en_passant
+            && self.halfmove_clock == other.halfmove_clock
+            && self.fullmove_number == other.fullmove_number
+    }
+}
+
+impl Eq for Board {}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.squares.hash(state);
+        self.turn.hash(state);
+        self.castling.hash(state);
+        self.used_castling_files().hash(state);
+        self.en_passant.hash(state);
+        self.halfmove_clock.hash(state);
+        self.fullmove_number.hash(state);
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Board::new()
+    }
+}
 
 impl Board {
     /// Creates new empty board
-    fn new() -> Self {
+    pub fn new() -> Self {
         Board {
             squares: [const { None }; N_SQUARES],
-            turn: WHITE,
-        }
-    }
-
-    /// Generates and returns a random board
-    fn random(rng: &mut rand::rngs::ThreadRng) -> Self {
-        const KING_MOVES: [(i8, i8); 8] = [
-            (1, 1),
-            (1, -1),
-            (1, 0),
-            (0, 1),
-            (0, -1),
-            (-1, 1),
-            (-1, -1),
-            (-1, 0),
-        ];
-        let mut board = Board::new();
-        board.turn = rng.random_bool(0.5);
+            pieces: [0; 6],
+            colors: [0; 2],
+            turn: Color::White,
+            castling: CastlingRights::default(),
+            castling_files: [CastlingFiles::STANDARD; 2],
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+        }
+    }
 
-        let mut white_king_pos: usize = 0;
-        let mut black_king_pos: usize = 0;
-        { // Here we start placing the kings
-            let mut occupied_squares = [false; N_SQUARES];
+    /// Creates the standard start position
+    pub fn start_position() -> Self {
+        Board::from_fen(START_FEN).unwrap()
+    }
 
-            //First randomly place white king
-            white_king_pos = rng.random_range(0..N_SQUARES) as usize;
-            
+    /// Returns the piece on `square`, if any
+    pub fn piece_at(&self, square: Square) -> Option<Piece> {
+        self.squares[square.index()]
+    }
 
-            board.squares[white_king_pos] = Some(Piece {
-                piece_type: PieceType::King,
-                color: WHITE,
-            });
+    /// Puts `piece` on `square` and returns the piece that stood there before, if any
+    pub fn set_piece(&mut self, square: Square, piece: Piece) -> Option<Piece> {
+        self.put(square.index(), Some(piece))
+    }
 
-            let (white_king_file, white_king_rank) = board_index_reverse(white_king_pos);
+    /// Clears `square` and returns the piece that stood there, if any
+    pub fn remove_piece(&mut self, square: Square) -> Option<Piece> {
+        self.take(square.index())
+    }
 
-            occupied_squares[white_king_pos] = true;
-            let mut free_squares_n: i8 = N_SQUARES as i8 - 1; // one square is already occupied by white king
+    /// Returns the color whose turn it is
+    pub fn side_to_move(&self) -> Color {
+        self.turn
+    }
+
+    pub fn set_side_to_move(&mut self, color: Color) {
+        self.turn = color;
+    }
 
-            for &delta in &KING_MOVES { // fill in the occupied_squares array
-                
-                let curr_file = white_king_file as i8 + delta.0;
-                let curr_rank = white_king_rank as i8 + delta.1;
+    pub fn castling_rights(&self) -> CastlingRights {
+        self.castling
+    }
 
-                if curr_file < 0 || curr_rank < 0 {continue;}
-                if curr_file >= 8 || curr_rank >= 8 {continue;}
+    pub fn set_castling_rights(&mut self, castling: CastlingRights) {
+        self.castling = castling;
+    }
 
-                occupied_squares[board_index(curr_file as usize, curr_rank as usize)] = true;
-                free_squares_n -= 1;
-            }
+    /// Returns the files that `color` castles from
+    pub fn castling_files(&self, color: Color) -> CastlingFiles {
+        self.castling_files[color as usize]
+    }
+
+    pub fn set_castling_files(&mut self, color: Color, files: CastlingFiles) {
+        self.castling_files[color as usize] = files;
+    }
+
+    /// Returns the king, kingside rook and queenside rook file of each color,
+    /// leaving out the files that no castling right uses
+    fn used_castling_files(&self) -> [Option<u8>; 6] {
+        let [white, black] = self.castling_files;
+        let rights = self.castling;
+        let white_castles = rights.white_kingside || rights.white_queenside;
+        let black_castles = rights.black_kingside || rights.black_queenside;
+
+        [
+            white_castles.then_some(white.king),
+            rights.white_kingside.then_some(white.kingside_rook),
+            rights.white_queenside.then_some(white.queenside_rook),
+            black_castles.then_some(black.king),
+            rights.black_kingside.then_some(black.kingside_rook),
+            rights.black_queenside.then_some(black.queenside_rook),
+        ]
+    }
 
-            // -4 because the white king already occupies at least 4 squares (when in corner)
-            let mut free_square_indexes: [usize; N_SQUARES - 4] = [0; N_SQUARES - 4]; 
+    /// Returns the square behind a pawn that just moved two squares, if any
+    pub fn en_passant(&self) -> Option<Square> {
+        self.en_passant.map(|index| Square(index as u8))
+    }
+
+    pub fn set_en_passant(&mut self, square: Option<Square>) {
+        self.en_passant = square.map(Square::index);
+    }
+
+    pub fn halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
+    }
+
+    pub fn set_halfmove_clock(&mut self, halfmove_clock: u32) {
+        self.halfmove_clock = halfmove_clock;
+    }
+
+    pub fn fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
 
-            // j counts the current index of free_square_indexes 
-            let mut j: usize = 0;
+    pub fn set_fullmove_number(&mut self, fullmove_number: u32) {
+        self.fullmove_number = fullmove_number;
+    }
 
-            for i in 0..N_SQUARES {
-                if occupied_squares[i] {continue;} 
+    /// Generates and returns a random legal board with the material described by `config`.
+    /// The board only depends on the numbers drawn from `rng`, so a seeded generator
+    /// such as `rand_chacha::ChaCha8Rng` always gives the same board for the same seed.
+    ///
+    /// Panics if no board is found within `config.max_attempts`, see `try_random`
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, config: &RandomConfig) -> Self {
+        match Board::try_random(rng, config) {
+            Ok(board) => board,
+            Err(error) => panic!("{}", error),
+        }
+    }
 
-                free_square_indexes[j] = i;
-                j += 1;
+    /// Same as `random`, but returns an error instead of panicking when
+    /// `config.max_attempts` placements did not give a legal board in `config.target_state`
+    pub fn try_random<R: Rng + ?Sized>(rng: &mut R, config: &RandomConfig) -> Result<Self, GenerateError> {
+        config.check();
+
+        for _ in 0..config.max_attempts {
+            let mut board = Board::place_pieces(rng, config);
+            if !board.is_legal() {continue;}
+            if config.reachable && !board.is_reachable() {continue;}
+
+            if config.castling_probability > 0.0 {
+                board.castling = board.random_castling(rng, config.castling_probability);
+            }
+            board.en_passant = board.random_en_passant(rng, config.en_passant, config.en_passant_probability);
+            board.set_random_clocks(rng, config.halfmove_clock, config.fullmove_number);
+
+            // The castling rights and the en passant square limit the last move
+            if config.last_move && !board.has_last_move() {continue;}
+
+            // Castling and en passant can add legal moves, so the state is only known now
+            if let Some(target) = config.target_state
+                && board.game_state() != target {
+                continue;
             }
 
-            black_king_pos = free_square_indexes[rng.random_range(0..free_squares_n) as usize];
+            return Ok(board);
+        }
+
+        Err(GenerateError::AttemptsExhausted { attempts: config.max_attempts })
+    }
+
+    /// Places the kings and the material described by `config` on random squares.
+    /// The kings are never adjacent and pawns never stand on the first or last rank,
+    /// but the board can still be illegal (e.g. the side not to move can be in check)
+    fn place_pieces<R: Rng + ?Sized>(rng: &mut R, config: &RandomConfig) -> Self {
+        if let Some(profile) = config.profile {
+            return profile.place_pieces(rng, config);
+        }
+
+        let mut board = Board::new();
+        board.turn = if rng.random_bool(0.5) { Color::White } else { Color::Black };
+
+        { // Here we start placing the kings
+            let white_king_pos = rng.random_range(0..N_SQUARES);
+            board.put(white_king_pos, Some(Piece::new(PieceType::King, Color::White)));
 
-            board.squares[black_king_pos] = Some(Piece {
-                piece_type: PieceType::King,
-                color: BLACK,
-            });
+            // The black king can go anywhere but on or next to the white king
+            let free = !(bitboard::KING_ATTACKS[white_king_pos] | bitboard::bit(white_king_pos));
+            let free_squares_n = free.count_ones() as i8;
 
+            let black_king_pos = bitboard::squares(free).nth(rng.random_range(0..free_squares_n) as usize).unwrap();
+            board.put(black_king_pos, Some(Piece::new(PieceType::King, Color::Black)));
         } // Here we end placing the kings
-        
-        // TODO: place the rest of the pieces
 
-        return board;
+        { // Here we start placing the rest of the pieces
+            let mut free_square_indexes: [usize; N_SQUARES] = [0; N_SQUARES];
+            let mut free_squares_n: usize = 0;
+
+            // Pawns go first, and only to the squares from the second to the seventh rank
+            for i in bitboard::squares(bitboard::PAWN_RANKS & !board.occupied()) {
+                free_square_indexes[free_squares_n] = i;
+                free_squares_n += 1;
+            }
+
+            for (color, material) in [(Color::White, &config.white), (Color::Black, &config.black)] {
+                let count = material.pawns;
+                for _ in 0..rng.random_range(count.min..=count.max) {
+                    let pos = take_random_square(rng, &mut free_square_indexes, &mut free_squares_n);
+                    board.put(pos, Some(Piece { piece_type: PieceType::Pawn, color }));
+                }
+            }
+
+            // Now every square that is still empty is available to the other pieces
+            free_squares_n = 0;
+            for i in bitboard::squares(!board.occupied()) {
+                free_square_indexes[free_squares_n] = i;
+                free_squares_n += 1;
+            }
+
+            for (color, material) in [(Color::White, &config.white), (Color::Black, &config.black)] {
+                for (piece_type, count) in material.counts() {
+                    if piece_type == PieceType::Pawn {continue;}
+
+                    for _ in 0..rng.random_range(count.min..=count.max) {
+                        let pos = take_random_square(rng, &mut free_square_indexes, &mut free_squares_n);
+                        board.put(pos, Some(Piece { piece_type, color }));
+                    }
+                }
+            }
+        } // Here we end placing the rest of the pieces
+
+        board
     }
     
-    /// Returns the fen representation of the board
-    fn to_str_fen(&self) -> String{
+    /// Returns whether the board is a legal position: both sides have exactly one king,
+    /// no pawn stands on the first or last rank, every castling right has its king and rook
+    /// on their original squares, the en passant square (if any) could have been left by
+    /// the last move, the side not to move is not in check
+    /// and the check of the side to move (if any) could have been given by the last move
+    pub fn is_legal(&self) -> bool {
+        let kings = self.pieces[PieceType::King as usize];
+        if (kings & self.colors[0]).count_ones() != 1 || (kings & self.colors[1]).count_ones() != 1 {
+            return false;
+        }
+        if self.pieces[PieceType::Pawn as usize] & !bitboard::PAWN_RANKS != 0 {
+            return false;
+        }
+
+        if !self.castling.is_subset_of(self.supported_castling()) {
+            return false;
+        }
+
+        if let Some(ep) = self.en_passant
+            && !self.plausible_en_passant_squares().contains(&ep) {
+            return false;
+        }
+
+        let their_king = self.king_square(!self.turn).unwrap();
+        if self.is_attacked(their_king, self.turn) {
+            return false;
+        }
+
+        !self.has_impossible_check()
+    }
+
+    /// Returns whether the side to move is in a check that no move could have given:
+    /// more than two checkers, a double check without a discovering slider,
+    /// or two checkers on the same line through the king
+    fn has_impossible_check(&self) -> bool {
+        let checkers = self.checkers();
+
+        match checkers[..] {
+            [] | [_] => false,
+            [a, b] => {
+                let is_slider = |index: usize| match &self.squares[index] {
+                    Some(piece) => attacks::is_slider(piece.piece_type),
+                    None => false,
+                };
+                let king = self.king_square(self.turn).unwrap();
+
+                !(is_slider(a) || is_slider(b)) || attacks::aligned(king, a, b)
+            },
+            _ => true,
+        }
+    }
+
+    /// Returns how many pieces of each type both sides have,
+    /// indexed by `Color` and then by `PieceType`
+    pub(crate) fn piece_counts(&self) -> [[u8; 6]; 2] {
+        let mut counts = [[0; 6]; 2];
+
+        for piece in self.squares.iter().flatten() {
+            counts[piece.color as usize][piece.piece_type as usize] += 1;
+        }
+        counts
+    }
+
+    /// Returns the fen representation of the board. Castling rights are written as in X-FEN:
+    /// "KQkq" for the outermost rooks, so standard positions get a plain fen, and the rook
+    /// file (e.g. "Gq") for a Chess960 rook with another rook further out on the same side
+    pub fn to_str_fen(&self) -> String{
+        self.fen(false)
+    }
+
+    /// Same as `to_str_fen`, but castling rights are written as Shredder-FEN does,
+    /// with the rook files ("HAha") instead of "KQkq"
+    pub fn to_str_shredder_fen(&self) -> String {
+        self.fen(true)
+    }
+
+    fn fen(&self, shredder: bool) -> String {
         let mut fen = String::new();
         for rank in (0..8).rev() {
 
@@ -165,17 +613,26 @@ impl Board {
 
         fen.push(' ');
         fen.push(match self.turn {
-            WHITE => 'w',
-            BLACK => 'b',
+            Color::White => 'w',
+            Color::Black => 'b',
         });
 
-        fen.push_str(" - - 0 1");
+        fen.push(' ');
+        fen.push_str(&self.castling_to_str_fen(shredder));
+
+        fen.push(' ');
+        match self.en_passant {
+            Some(index) => fen.push_str(&Square(index as u8).to_string()),
+            None => fen.push('-'),
+        }
 
-        return fen;
+        fen.push_str(&format!(" {} {}", self.halfmove_clock, self.fullmove_number));
+
+        fen
     }
 
     /// Displays the board as a 2d image
-    fn to_str(&self) -> String{
+    pub fn to_str(&self) -> String{
         let mut s = String::new();
 
         for rank in (0..8).rev() {
@@ -190,14 +647,18 @@ impl Board {
             s.push('\n');
         }
         s.push_str(&format!("Turn: {:?}", self.turn));
-        return s;
+        s
     }
 }
 
 impl Piece {
+    pub fn new(piece_type: PieceType, color: Color) -> Self {
+        Piece { piece_type, color }
+    }
+
     /// Returns the corresponding ASCII character of the Piece.
     /// For example Pawn would return b'p'
-    fn to_char(&self) -> u8 {
+    pub fn to_char(&self) -> u8 {
         let letter = match self.piece_type {
             PieceType::Pawn => b'p',
             PieceType::Rook => b'r',
@@ -207,16 +668,209 @@ impl Piece {
             PieceType::King => b'k',
         };
         match self.color {
-            WHITE => letter.to_ascii_uppercase(),
-            BLACK => letter,
+            Color::White => letter.to_ascii_uppercase(),
+            Color::Black => letter,
+        }
+    }
+
+    /// Returns the Piece for an ASCII character, the inverse of `to_char`.
+    /// For example b'N' would return a white Knight
+    pub fn from_char(c: u8) -> Option<Self> {
+        let piece_type = match c.to_ascii_lowercase() {
+            b'p' => PieceType::Pawn,
+            b'r' => PieceType::Rook,
+            b'n' => PieceType::Knight,
+            b'b' => PieceType::Bishop,
+            b'q' => PieceType::Queen,
+            b'k' => PieceType::King,
+            _ => return None,
+        };
+        let color = if c.is_ascii_uppercase() { Color::White } else { Color::Black };
+
+        Some(Piece { piece_type, color })
+    }
+}
+
+/// Removes a random square from the first `free_squares_n` entries of `free_square_indexes`
+/// and returns it. The gap is filled with the last free square, so the free squares
+/// always stay in the front of the array
+fn take_random_square<R: Rng + ?Sized>(
+    rng: &mut R,
+    free_square_indexes: &mut [usize; N_SQUARES],
+    free_squares_n: &mut usize,
+) -> usize {
+    let j = rng.random_range(0..*free_squares_n);
+    let pos = free_square_indexes[j];
+
+    *free_squares_n -= 1;
+    free_square_indexes[j] = free_square_indexes[*free_squares_n];
+
+    pos
+}
+
+impl PieceCount {
+    /// Creates a range that always gives exactly `n` pieces
+    pub const fn exactly(n: u8) -> Self {
+        PieceCount { min: n, max: n }
+    }
+
+    /// Creates a range from `min` to `max` pieces (both inclusive)
+    pub const fn between(min: u8, max: u8) -> Self {
+        PieceCount { min, max }
+    }
+}
+
+impl SideMaterial {
+    /// No pieces apart from the king
+    pub const NONE: SideMaterial = SideMaterial {
+        pawns: PieceCount::exactly(0),
+        knights: PieceCount::exactly(0),
+        bishops: PieceCount::exactly(0),
+        rooks: PieceCount::exactly(0),
+        queens: PieceCount::exactly(0),
+    };
+
+    /// Anything from a bare king up to the full starting material
+    pub const UP_TO_FULL: SideMaterial = SideMaterial {
+        pawns: PieceCount::between(0, 8),
+        knights: PieceCount::between(0, 2),
+        bishops: PieceCount::between(0, 2),
+        rooks: PieceCount::between(0, 2),
+        queens: PieceCount::between(0, 1),
+    };
+
+    /// Returns the ranges paired with the piece types they belong to
+    fn counts(&self) -> [(PieceType, PieceCount); 5] {
+        [
+            (PieceType::Pawn, self.pawns),
+            (PieceType::Knight, self.knights),
+            (PieceType::Bishop, self.bishops),
+            (PieceType::Rook, self.rooks),
+            (PieceType::Queen, self.queens),
+        ]
+    }
+
+    /// Returns the largest number of pieces (without the king) this side can get
+    fn max_pieces(&self) -> usize {
+        self.counts().iter().map(|(_, count)| count.max as usize).sum()
+    }
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerateError::AttemptsExhausted { attempts } => {
+                write!(f, "no matching position found in {} attempts", attempts)
+            },
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {}
+
+impl Default for RandomConfig {
+    fn default() -> Self {
+        RandomConfig {
+            white: SideMaterial::UP_TO_FULL,
+            black: SideMaterial::UP_TO_FULL,
+            castling_probability: 0.0,
+            en_passant: EnPassantMode::Never,
+            en_passant_probability: 1.0,
+            halfmove_clock: ClockMode::Fixed(0),
+            fullmove_number: ClockMode::Fixed(1),
+            target_state: None,
+            max_attempts: 1_000_000,
+            reachable: false,
+            last_move: false,
+            profile: None,
+        }
+    }
+}
+
+impl From<MaterialSpec> for RandomConfig {
+    /// The default config with the material of `spec`
+    fn from(spec: MaterialSpec) -> Self {
+        RandomConfig {
+            white: spec.white,
+            black: spec.black,
+            ..RandomConfig::default()
+        }
+    }
+}
+
+impl RandomConfig {
+    /// Panics if the config describes material that can not be placed on the board
+    fn check(&self) {
+        for material in [&self.white, &self.black] {
+            for (_, count) in material.counts() {
+                assert!(
+                    count.min <= count.max,
+                    "piece count range is empty: min={}, max={}", count.min, count.max);
+            }
+        }
+
+        let max_pieces = 2 + self.white.max_pieces() + self.black.max_pieces();
+        assert!(
+            max_pieces <= N_SQUARES,
+            "too many pieces to fit on the board: {}", max_pieces);
+
+        // Both kings may stand on the ranks reserved for pawns
+        let max_pawns = self.white.pawns.max as usize + self.black.pawns.max as usize;
+        assert!(
+            max_pawns <= PAWN_SQUARES - 2,
+            "too many pawns to fit on the second to seventh rank: {}", max_pawns);
+
+        for (name, probability) in [
+            ("castling", self.castling_probability),
+            ("en passant", self.en_passant_probability),
+        ] {
+            assert!(
+                (0.0..=1.0).contains(&probability),
+                "{} probability out of range: {}", name, probability);
         }
+
+        assert!(
+            self.fullmove_number != ClockMode::Fixed(0),
+            "fullmove number starts at 1");
     }
 }
 
 pub fn random_fen() -> String {
     let mut rng = rand::rng();
-    let board = Board::random(&mut rng);
+    let board = Board::random(&mut rng, &RandomConfig::default());
     board.to_str_fen()
 }
 
+/// Returns the start position with white giving `odds`
+pub fn odds_fen(odds: Odds) -> String {
+    Board::odds(odds, Color::White).to_str_fen()
+}
 
+/// Returns the start position with white giving random material worth at most `budget` pawns,
+/// see `Board::random_odds`
+pub fn random_odds_fen(budget: u32) -> String {
+    let mut rng = rand::rng();
+    Board::random_odds(&mut rng, budget, Color::White).to_str_fen()
+}
+
+/// Returns the position after `plies` random legal moves from the start position,
+/// or fewer if the game ends before
+pub fn random_playout_fen(plies: u32) -> String {
+    let mut rng = rand::rng();
+    Board::start_position().playout(&mut rng, &PlayoutConfig::fixed(plies)).to_str_fen()
+}
+
+/// Returns one of the 960 Chess960 start positions, all equally likely
+pub fn random_chess960_fen() -> String {
+    let mut rng = rand::rng();
+    Board::random_chess960(&mut rng).to_str_fen()
+}
+
+/// Same as `random_fen`, but the position only depends on `seed`.
+/// The generator is ChaCha8, so a seed gives the same fen on every platform
+/// (as long as the crate version stays the same)
+pub fn random_fen_seeded(seed: u64) -> String {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let board = Board::random(&mut rng, &RandomConfig::default());
+    board.to_str_fen()
+}
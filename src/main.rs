@@ -1,4 +1,529 @@
-fn main(){
-    let fen = fen_generator::random_fen();
-    println!("{}", fen);
+use std::process::ExitCode;
+
+use fen_generator::{
+    Board, Color, Dedup, MaterialSpec, Odds, Piece, PlayoutConfig, Profile, RandomConfig, SampleSpace,
+    UniqueGenerator, CHESS960_POSITIONS, TRANSCENDENTAL_SETUPS,
+};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+const USAGE: &str = "\
+usage: fen-generator [--seed <u64>] [--material <spec, e.g. KRPvKR>]
+                     [--profile <opening | middlegame | endgame | pawn-endgame | minor-piece-endgame>]
+                     [--count <n>] [--unique | --bloom <false positive rate>] [--reachable] [--last-move]
+                     [--polyglot-key]
+       fen-generator perft <fen> <depth>
+       fen-generator retro <fen>
+       fen-generator estimate [--material <spec>] [--samples <n>] [--seed <u64>]
+       fen-generator chess960 [--index <0..959> | --seed <u64>] [--shredder]
+       fen-generator chess960 --double [--index <white>,<black> | --seed <u64>]
+       fen-generator chess960 --transcendental [--index <white>,<black> | --seed <u64>]
+       fen-generator odds <pawn-and-move | knight | rook | queen> [--giver <white | black>]
+       fen-generator odds --budget <pawns> [--giver <white | black>] [--seed <u64>]
+       fen-generator playout [--fen <fen>] [--plies <n | min..max>] [--count <n>] [--seed <u64>]
+                             [--capture <bonus>] [--check <bonus>] [--center <bonus>]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("perft") => perft(&args[1..]),
+        Some("retro") => retro(&args[1..]),
+        Some("estimate") => estimate(&args[1..]),
+        Some("chess960") => chess960(&args[1..]),
+        Some("odds") => odds(&args[1..]),
+        Some("playout") => playout(&args[1..]),
+        _ => generate(&args),
+    }
+}
+
+/// Prints random fens, one per line, optionally followed by a tab and the polyglot key
+fn generate(args: &[String]) -> ExitCode {
+    let mut seed: Option<u64> = None;
+    let mut profile: Option<Profile> = None;
+    let mut material: Option<MaterialSpec> = None;
+    let mut count: usize = 1;
+    let mut reachable = false;
+    let mut last_move = false;
+    let mut dedup: Option<Dedup> = None;
+    let mut polyglot = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let Some(value) = args.next().and_then(|value| value.parse().ok()) else {
+                    eprintln!("--seed needs an unsigned 64-bit integer\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                seed = Some(value);
+            },
+            "--material" => {
+                let Some(spec) = args.next() else {
+                    eprintln!("--material needs a spec such as KRPvKR\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                match spec.parse::<MaterialSpec>() {
+                    Ok(spec) => material = Some(spec),
+                    Err(error) => {
+                        eprintln!("{}\n  {}\n  {}^", error, spec, " ".repeat(error.offset));
+                        return ExitCode::FAILURE;
+                    },
+                }
+            },
+            "--profile" => {
+                let Some(name) = args.next() else {
+                    eprintln!("--profile needs a profile name\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                match name.parse::<Profile>() {
+                    Ok(value) => profile = Some(value),
+                    Err(error) => {
+                        eprintln!("{}", error);
+                        return ExitCode::FAILURE;
+                    },
+                }
+            },
+            "--count" => {
+                let Some(value) = args.next().and_then(|value| value.parse().ok()) else {
+                    eprintln!("--count needs a non-negative integer\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                count = value;
+            },
+            "--reachable" => reachable = true,
+            "--last-move" => last_move = true,
+            "--unique" => dedup = Some(Dedup::HashSet),
+            "--bloom" => {
+                let Some(rate) = args.next().and_then(|value| value.parse().ok()).filter(|&rate| rate > 0.0 && rate < 1.0) else {
+                    eprintln!("--bloom needs a false positive rate between 0 and 1\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                dedup = Some(Dedup::Bloom { expected: 0, false_positive_rate: rate });
+            },
+            "--polyglot-key" => polyglot = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            },
+            _ => {
+                eprintln!("unknown argument: {}\n{}", arg, USAGE);
+                return ExitCode::FAILURE;
+            },
+        }
+    }
+
+    // A material spec replaces the material of the profile, but keeps where it puts the pieces
+    let mut config = profile.map_or_else(RandomConfig::default, Profile::config);
+    if let Some(spec) = material {
+        config.white = spec.white;
+        config.black = spec.black;
+    }
+    config.reachable = reachable;
+    config.last_move = last_move;
+
+    // Same generators as random_fen and random_fen_seeded, so the same seed gives the same fen
+    let mut rng: Box<dyn RngCore> = match seed {
+        Some(seed) => Box::new(ChaCha8Rng::seed_from_u64(seed)),
+        None => Box::new(rand::rng()),
+    };
+
+    // The Bloom filter is sized for the whole run
+    let mut unique = dedup.map(|dedup| match dedup {
+        Dedup::Bloom { false_positive_rate, .. } => UniqueGenerator::new(config, Dedup::Bloom { expected: count, false_positive_rate }),
+        dedup => UniqueGenerator::new(config, dedup),
+    });
+
+    for _ in 0..count {
+        let board = match &mut unique {
+            Some(unique) => unique.next_board(&mut rng),
+            None => Board::try_random(&mut rng, &config),
+        };
+        match (board, polyglot) {
+            (Ok(board), true) => println!("{}\t{:016x}", board.to_str_fen(), board.polyglot_key()),
+            (Ok(board), false) => println!("{}", board.to_str_fen()),
+            (Err(error), _) => {
+                eprintln!("{}", error);
+                return ExitCode::FAILURE;
+            },
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Prints the perft count below every legal move and the total
+fn perft(args: &[String]) -> ExitCode {
+    let [fen, depth] = args else {
+        eprintln!("perft needs a fen and a depth\n{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let board = match Board::from_fen(fen) {
+        Ok(board) if board.is_legal() => board,
+        Ok(_) => {
+            eprintln!("perft needs a legal position: {}", fen);
+            return ExitCode::FAILURE;
+        },
+        Err(error) => {
+            eprintln!("{}", error);
+            return ExitCode::FAILURE;
+        },
+    };
+    let Ok(depth) = depth.parse::<u32>() else {
+        eprintln!("depth must be a non-negative integer, got {:?}", depth);
+        return ExitCode::FAILURE;
+    };
+
+    let moves = fen_generator::divide(&board, depth);
+    for (mv, nodes) in &moves {
+        println!("{}: {}", mv, nodes);
+    }
+
+    // At depth 0 nothing is divided, the position itself is the one node
+    let total: u64 = match depth {
+        0 => 1,
+        _ => moves.iter().map(|&(_, nodes)| nodes).sum(),
+    };
+    println!("\nNodes searched: {}", total);
+
+    ExitCode::SUCCESS
+}
+
+/// Prints every move that can have led to the position, or why there is none
+fn retro(args: &[String]) -> ExitCode {
+    let [fen] = args else {
+        eprintln!("retro needs a fen\n{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let board = match Board::from_fen(fen) {
+        Ok(board) => board,
+        Err(error) => {
+            eprintln!("{}", error);
+            return ExitCode::FAILURE;
+        },
+    };
+    if let Err(error) = board.check_last_move() {
+        println!("no legal last move: {}", error);
+        return ExitCode::FAILURE;
+    }
+
+    // The move, the piece it took or "-", and the position before it
+    for un_move in board.un_moves() {
+        let captured = match un_move.captured {
+            Some(piece_type) => Piece::new(piece_type, board.side_to_move()).to_char() as char,
+            None => '-',
+        };
+        println!("{}\t{}\t{}", un_move.mv, captured, un_move.previous.to_str_fen());
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Prints an estimate of the number of legal positions, for some material or overall
+fn estimate(args: &[String]) -> ExitCode {
+    let mut space: Option<SampleSpace> = None;
+    let mut samples: u64 = 100_000;
+    let mut seed: Option<u64> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--material" => {
+                let Some(spec) = args.next() else {
+                    eprintln!("--material needs a spec such as KRPvKR\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                match spec.parse::<MaterialSpec>() {
+                    Ok(spec) => space = Some(SampleSpace::from_spec(&spec)),
+                    Err(error) => {
+                        eprintln!("{}\n  {}\n  {}^", error, spec, " ".repeat(error.offset));
+                        return ExitCode::FAILURE;
+                    },
+                }
+            },
+            "--samples" => {
+                let Some(value) = args.next().and_then(|value| value.parse().ok()).filter(|&n| n > 0) else {
+                    eprintln!("--samples needs a positive integer\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                samples = value;
+            },
+            "--seed" => {
+                let Some(value) = args.next().and_then(|value| value.parse().ok()) else {
+                    eprintln!("--seed needs an unsigned 64-bit integer\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                seed = Some(value);
+            },
+            _ => {
+                eprintln!("unknown argument: {}\n{}", arg, USAGE);
+                return ExitCode::FAILURE;
+            },
+        }
+    }
+
+    let space = space.unwrap_or_else(SampleSpace::all);
+    if space.size() == 0.0 {
+        eprintln!("the material does not fit on the board");
+        return ExitCode::FAILURE;
+    }
+
+    let estimate = match seed {
+        Some(seed) => fen_generator::estimate(&mut ChaCha8Rng::seed_from_u64(seed), &space, samples),
+        None => fen_generator::estimate(&mut rand::rng(), &space, samples),
+    };
+    let (low, high) = estimate.interval();
+
+    println!("sample space: {:.4e} placements", estimate.space_size);
+    println!("legal: {} of {} samples ({:.4}%)", estimate.legal, estimate.samples, 100.0 * estimate.legal_ratio());
+    println!("estimate: {:.4e} legal positions", estimate.count());
+    println!("95% confidence interval: {:.4e} .. {:.4e}", low, high);
+
+    ExitCode::SUCCESS
+}
+
+/// Which start positions the chess960 subcommand picks from
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Variant {
+    Chess960,
+    Double,
+    Transcendental,
+}
+
+/// Prints a Chess960 start position, chosen by its index or at random. Double Chess960
+/// and Transcendental Chess positions give each side its own index and are always
+/// written in Shredder-FEN
+fn chess960(args: &[String]) -> ExitCode {
+    let mut variant = Variant::Chess960;
+    let mut index: Option<&str> = None;
+    let mut seed: Option<u64> = None;
+    let mut shredder = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--double" => variant = Variant::Double,
+            "--transcendental" => variant = Variant::Transcendental,
+            "--index" => {
+                let Some(value) = args.next() else {
+                    eprintln!("--index needs a number, or two numbers like 12,345\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                index = Some(value);
+            },
+            "--seed" => {
+                let Some(value) = args.next().and_then(|value| value.parse().ok()) else {
+                    eprintln!("--seed needs an unsigned 64-bit integer\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                seed = Some(value);
+            },
+            "--shredder" => shredder = true,
+            _ => {
+                eprintln!("unknown argument: {}\n{}", arg, USAGE);
+                return ExitCode::FAILURE;
+            },
+        }
+    }
+
+    let board = match index {
+        Some(_) if seed.is_some() => {
+            eprintln!("--seed only applies without --index, a numbered setup is not random\n{}", USAGE);
+            return ExitCode::FAILURE;
+        },
+        Some(index) => {
+            let board = match variant {
+                Variant::Chess960 => index.parse().ok().and_then(Board::chess960),
+                Variant::Double => parse_pair(index).and_then(|(white, black)| Board::double_chess960(white, black)),
+                Variant::Transcendental => parse_pair(index).and_then(|(white, black)| Board::transcendental(white, black)),
+            };
+            let Some(board) = board else {
+                let range = if variant == Variant::Transcendental { TRANSCENDENTAL_SETUPS } else { CHESS960_POSITIONS };
+                let form = if variant == Variant::Chess960 { "a number" } else { "two numbers like 12,345" };
+                eprintln!("--index needs {} from 0 to {}\n{}", form, range - 1, USAGE);
+                return ExitCode::FAILURE;
+            };
+            board
+        },
+        None => {
+            let mut rng: Box<dyn RngCore> = match seed {
+                Some(seed) => Box::new(ChaCha8Rng::seed_from_u64(seed)),
+                None => Box::new(rand::rng()),
+            };
+            match variant {
+                Variant::Chess960 => Board::random_chess960(&mut rng),
+                Variant::Double => Board::random_double_chess960(&mut rng),
+                Variant::Transcendental => Board::random_transcendental(&mut rng),
+            }
+        },
+    };
+
+    if shredder || variant != Variant::Chess960 {
+        println!("{}", board.to_str_shredder_fen());
+    } else {
+        println!("{}", board.to_str_fen());
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Parses "12,345" into (12, 345)
+fn parse_pair(s: &str) -> Option<(u16, u16)> {
+    let (first, second) = s.split_once(',')?;
+    Some((first.trim().parse().ok()?, second.trim().parse().ok()?))
+}
+
+/// Prints the start position with odds given, either one of the classic handicaps
+/// or random material up to a budget
+fn odds(args: &[String]) -> ExitCode {
+    let mut odds: Option<Odds> = None;
+    let mut budget: Option<u32> = None;
+    let mut giver = Color::White;
+    let mut seed: Option<u64> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "pawn-and-move" => odds = Some(Odds::PawnAndMove),
+            "knight" => odds = Some(Odds::Knight),
+            "rook" => odds = Some(Odds::Rook),
+            "queen" => odds = Some(Odds::Queen),
+            "--budget" => {
+                let Some(value) = args.next().and_then(|value| value.parse().ok()) else {
+                    eprintln!("--budget needs a number of pawns\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                budget = Some(value);
+            },
+            "--giver" => {
+                giver = match args.next().map(String::as_str) {
+                    Some("white") => Color::White,
+                    Some("black") => Color::Black,
+                    _ => {
+                        eprintln!("--giver needs white or black\n{}", USAGE);
+                        return ExitCode::FAILURE;
+                    },
+                };
+            },
+            "--seed" => {
+                let Some(value) = args.next().and_then(|value| value.parse().ok()) else {
+                    eprintln!("--seed needs an unsigned 64-bit integer\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                seed = Some(value);
+            },
+            _ => {
+                eprintln!("unknown argument: {}\n{}", arg, USAGE);
+                return ExitCode::FAILURE;
+            },
+        }
+    }
+
+    let board = match (odds, budget) {
+        (Some(_), None) if seed.is_some() => {
+            eprintln!("--seed only applies to --budget, a fixed handicap is not random\n{}", USAGE);
+            return ExitCode::FAILURE;
+        },
+        (Some(odds), None) => Board::odds(odds, giver),
+        (None, Some(budget)) => match seed {
+            Some(seed) => Board::random_odds(&mut ChaCha8Rng::seed_from_u64(seed), budget, giver),
+            None => Board::random_odds(&mut rand::rng(), budget, giver),
+        },
+        _ => {
+            eprintln!("odds needs either a handicap or --budget\n{}", USAGE);
+            return ExitCode::FAILURE;
+        },
+    };
+    println!("{}", board.to_str_fen());
+
+    ExitCode::SUCCESS
+}
+
+/// Prints positions reached by random legal moves from the start position or a given fen
+fn playout(args: &[String]) -> ExitCode {
+    let mut board = Board::start_position();
+    let mut config = PlayoutConfig::default();
+    let mut count: usize = 1;
+    let mut seed: Option<u64> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fen" => {
+                let Some(fen) = args.next() else {
+                    eprintln!("--fen needs a position\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                match Board::from_fen(fen) {
+                    Ok(from) if from.is_legal() => board = from,
+                    Ok(_) => {
+                        eprintln!("--fen needs a legal position: {}", fen);
+                        return ExitCode::FAILURE;
+                    },
+                    Err(error) => {
+                        eprintln!("{}", error);
+                        return ExitCode::FAILURE;
+                    },
+                }
+            },
+            "--plies" => {
+                let Some((min, max)) = args.next().and_then(|value| parse_range(value)) else {
+                    eprintln!("--plies needs a number or a range like 10..40\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                config.min_plies = min;
+                config.max_plies = max;
+            },
+            "--count" => {
+                let Some(value) = args.next().and_then(|value| value.parse().ok()) else {
+                    eprintln!("--count needs a non-negative integer\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                count = value;
+            },
+            "--seed" => {
+                let Some(value) = args.next().and_then(|value| value.parse().ok()) else {
+                    eprintln!("--seed needs an unsigned 64-bit integer\n{}", USAGE);
+                    return ExitCode::FAILURE;
+                };
+                seed = Some(value);
+            },
+            "--capture" | "--check" | "--center" => {
+                let Some(bonus) = args.next().and_then(|value| value.parse().ok()).filter(|&bonus: &f64| bonus >= 0.0) else {
+                    eprintln!("{} needs a non-negative number\n{}", arg, USAGE);
+                    return ExitCode::FAILURE;
+                };
+                match arg.as_str() {
+                    "--capture" => config.weights.capture = bonus,
+                    "--check" => config.weights.check = bonus,
+                    _ => config.weights.center = bonus,
+                }
+            },
+            _ => {
+                eprintln!("unknown argument: {}\n{}", arg, USAGE);
+                return ExitCode::FAILURE;
+            },
+        }
+    }
+
+    let mut rng: Box<dyn RngCore> = match seed {
+        Some(seed) => Box::new(ChaCha8Rng::seed_from_u64(seed)),
+        None => Box::new(rand::rng()),
+    };
+    for _ in 0..count {
+        println!("{}", board.playout(&mut rng, &config).to_str_fen());
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Parses "40" into (40, 40) and "10..40" into (10, 40)
+fn parse_range(s: &str) -> Option<(u32, u32)> {
+    let (min, max) = match s.split_once("..") {
+        Some((min, max)) => (min.trim().parse().ok()?, max.trim().parse().ok()?),
+        None => (s.parse().ok()?, s.parse().ok()?),
+    };
+    (min <= max).then_some((min, max))
 }
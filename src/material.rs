@@ -0,0 +1,271 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Color, Piece, PieceCount, PieceType, SideMaterial, N_SQUARES, PAWN_SQUARES};
+
+/// Material for both sides, written in endgame notation such as "KQvKR", "KRPPvKRP"
+/// or with ranges "K+2..4P v K+R".
+///
+/// Each side is a list of pieces (K, Q, R, B, N, P, in any case), optionally joined by '+'
+/// and surrounded by spaces. A piece can be preceded by a count ("3P") or an inclusive
+/// range ("2..4P"), and the same piece can be listed more than once ("KRPP").
+/// Every side needs exactly one king, and the sides are separated by 'v' or "vs"
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct MaterialSpec {
+    pub white: SideMaterial,
+    pub black: SideMaterial,
+}
+
+/// Exact material: how many pieces of each type both sides have, kings included.
+/// Parses from the same notation as `MaterialSpec`, but without ranges
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Material {
+    /// Indexed by `Color` and then by `PieceType`
+    counts: [[u8; 6]; 2],
+}
+
+/// What is wrong with a material spec
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MaterialErrorKind {
+    /// A character that is not allowed at this position
+    UnexpectedChar(char),
+    /// The spec ended where a piece or the 'v' between the sides was expected
+    UnexpectedEnd,
+    /// A side without a king
+    MissingKing,
+    /// A side with more than one king, or a count in front of the king
+    ExtraKing,
+    /// A count that does not fit, or a range whose start is above its end
+    BadCount,
+    /// More pieces than fit on the board
+    TooManyPieces,
+    /// A range where exact material was needed
+    NotExact,
+}
+
+/// An error from parsing a `MaterialSpec`, with the byte offset where it was found
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaterialError {
+    pub offset: usize,
+    pub kind: MaterialErrorKind,
+}
+
+impl fmt::Display for MaterialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid material at offset {}: ", self.offset)?;
+
+        match &self.kind {
+            MaterialErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            MaterialErrorKind::UnexpectedEnd => write!(f, "expected a piece or 'v' between the sides"),
+            MaterialErrorKind::MissingKing => write!(f, "side has no king"),
+            MaterialErrorKind::ExtraKing => write!(f, "side can only have one king"),
+            MaterialErrorKind::BadCount => write!(f, "count out of range"),
+            MaterialErrorKind::TooManyPieces => write!(f, "too many pieces to fit on the board"),
+            MaterialErrorKind::NotExact => write!(f, "expected exact counts, not ranges"),
+        }
+    }
+}
+
+impl std::error::Error for MaterialError {}
+
+/// Reads a material spec one byte at a time
+struct Parser<'a> {
+    spec: &'a [u8],
+    offset: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, kind: MaterialErrorKind) -> MaterialError {
+        MaterialError { offset: self.offset, kind }
+    }
+
+    fn unexpected(&self) -> MaterialError {
+        match self.peek() {
+            Some(c) => self.error(MaterialErrorKind::UnexpectedChar(c as char)),
+            None => self.error(MaterialErrorKind::UnexpectedEnd),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.spec.get(self.offset).copied()
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek() == Some(b' ') {
+            self.offset += 1;
+        }
+    }
+
+    /// Returns whether the next characters are `s` (ignoring case), and skips them if so
+    fn eat(&mut self, s: &str) -> bool {
+        let end = self.offset + s.len();
+        if end <= self.spec.len() && self.spec[self.offset..end].eq_ignore_ascii_case(s.as_bytes()) {
+            self.offset = end;
+            return true;
+        }
+        false
+    }
+
+    /// Reads a decimal number, or returns None if there is no digit
+    fn number(&mut self) -> Result<Option<u8>, MaterialError> {
+        let start = self.offset;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.offset += 1;
+        }
+        if start == self.offset {
+            return Ok(None);
+        }
+
+        let digits = std::str::from_utf8(&self.spec[start..self.offset]).unwrap();
+        digits.parse()
+            .map(Some)
+            .map_err(|_| MaterialError { offset: start, kind: MaterialErrorKind::BadCount })
+    }
+
+    /// Reads one side, up to the 'v' or the end of the spec
+    fn side(&mut self) -> Result<SideMaterial, MaterialError> {
+        let start = self.offset;
+        let mut material = SideMaterial::NONE;
+        let mut has_king = false;
+
+        loop {
+            self.skip_spaces();
+            if self.peek().is_none() || self.peek().is_some_and(|c| c.eq_ignore_ascii_case(&b'v')) {
+                break;
+            }
+            if self.eat("+") {continue;}
+
+            let count_start = self.offset;
+            let count = match self.number()? {
+                None => PieceCount::exactly(1),
+                Some(min) if self.eat("..") => {
+                    let Some(max) = self.number()? else {
+                        return Err(self.unexpected());
+                    };
+                    if min > max {
+                        return Err(MaterialError { offset: count_start, kind: MaterialErrorKind::BadCount });
+                    }
+                    PieceCount::between(min, max)
+                },
+                Some(n) => PieceCount::exactly(n),
+            };
+
+            let Some(letter) = self.peek() else {
+                return Err(self.unexpected());
+            };
+            let target = match letter.to_ascii_uppercase() {
+                b'K' => {
+                    if has_king || count != PieceCount::exactly(1) {
+                        return Err(MaterialError { offset: count_start, kind: MaterialErrorKind::ExtraKing });
+                    }
+                    has_king = true;
+                    self.offset += 1;
+                    continue;
+                },
+                b'Q' => &mut material.queens,
+                b'R' => &mut material.rooks,
+                b'B' => &mut material.bishops,
+                b'N' => &mut material.knights,
+                b'P' => &mut material.pawns,
+                _ => return Err(self.unexpected()),
+            };
+
+            let (Some(min), Some(max)) = (target.min.checked_add(count.min), target.max.checked_add(count.max)) else {
+                return Err(MaterialError { offset: count_start, kind: MaterialErrorKind::BadCount });
+            };
+            *target = PieceCount::between(min, max);
+            self.offset += 1;
+        }
+
+        if !has_king {
+            return Err(MaterialError { offset: start, kind: MaterialErrorKind::MissingKing });
+        }
+        Ok(material)
+    }
+}
+
+impl FromStr for MaterialSpec {
+    type Err = MaterialError;
+
+    fn from_str(spec: &str) -> Result<Self, MaterialError> {
+        let mut parser = Parser { spec: spec.as_bytes(), offset: 0 };
+
+        let white = parser.side()?;
+        if !parser.eat("vs") && !parser.eat("v") {
+            return Err(parser.unexpected());
+        }
+        let black = parser.side()?;
+        if parser.peek().is_some() {
+            return Err(parser.unexpected());
+        }
+
+        // Same limits as `RandomConfig` checks: the kings plus the rest on the board,
+        // and the pawns on the second to seventh rank next to both kings
+        let max_pieces = 2 + white.max_pieces() + black.max_pieces();
+        let max_pawns = white.pawns.max as usize + black.pawns.max as usize;
+        if max_pieces > N_SQUARES || max_pawns > PAWN_SQUARES - 2 {
+            return Err(MaterialError { offset: 0, kind: MaterialErrorKind::TooManyPieces });
+        }
+
+        Ok(MaterialSpec { white, black })
+    }
+}
+
+impl MaterialSpec {
+    /// Returns the exact material if no side has a range with more than one count
+    pub fn exact(&self) -> Option<Material> {
+        let mut counts = [[0; 6]; 2];
+
+        for (color, material) in [(Color::White, &self.white), (Color::Black, &self.black)] {
+            for (piece_type, count) in material.counts() {
+                if count.min != count.max {
+                    return None;
+                }
+                counts[color as usize][piece_type as usize] = count.min;
+            }
+            counts[color as usize][PieceType::King as usize] = 1;
+        }
+
+        Some(Material { counts })
+    }
+}
+
+impl Material {
+    /// Returns how many pieces of this type and color there are
+    pub fn count(&self, color: Color, piece_type: PieceType) -> u8 {
+        self.counts[color as usize][piece_type as usize]
+    }
+
+    /// Returns every piece, white before black, and within a color kings first and pawns last.
+    /// Pieces of the same type are next to each other
+    pub fn pieces(&self) -> Vec<Piece> {
+        const ORDER: [PieceType; 6] = [
+            PieceType::King,
+            PieceType::Queen,
+            PieceType::Rook,
+            PieceType::Bishop,
+            PieceType::Knight,
+            PieceType::Pawn,
+        ];
+
+        let mut pieces = Vec::new();
+        for color in [Color::White, Color::Black] {
+            for piece_type in ORDER {
+                for _ in 0..self.count(color, piece_type) {
+                    pieces.push(Piece::new(piece_type, color));
+                }
+            }
+        }
+        pieces
+    }
+}
+
+impl FromStr for Material {
+    type Err = MaterialError;
+
+    fn from_str(spec: &str) -> Result<Self, MaterialError> {
+        spec.parse::<MaterialSpec>()?
+            .exact()
+            .ok_or(MaterialError { offset: 0, kind: MaterialErrorKind::NotExact })
+    }
+}
@@ -0,0 +1,300 @@
+use std::fmt;
+
+use crate::attacks::offset;
+use crate::bitboard::{bishop_attacks, rook_attacks, squares, KING_ATTACKS, KNIGHT_ATTACKS};
+use crate::castling::home_rank;
+use crate::{board_index, board_index_reverse, Board, CastlingFiles, Color, Piece, PieceType, Square};
+
+/// A move from one square to another. Castling is written as the king moving two squares,
+/// as in UCI
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Move {
+    pub from: Square,
+    pub to: Square,
+    /// The piece a pawn turns into on the last rank
+    pub promotion: Option<PieceType>,
+}
+
+const PROMOTIONS: [PieceType; 4] = [PieceType::Queen, PieceType::Rook, PieceType::Bishop, PieceType::Knight];
+
+impl Move {
+    pub fn new(from: Square, to: Square, promotion: Option<PieceType>) -> Self {
+        Move { from, to, promotion }
+    }
+
+    /// Parses a move in UCI notation such as "e2e4" or "e7e8q". The promotion letter
+    /// is lowercase, as UCI writes it
+    pub fn from_uci(uci: &str) -> Option<Self> {
+        if !uci.is_ascii() || !(4..=5).contains(&uci.len()) {
+            return None;
+        }
+
+        let from = Square::from_name(&uci[0..2])?;
+        let to = Square::from_name(&uci[2..4])?;
+        let promotion = match uci.as_bytes().get(4) {
+            None => None,
+            Some(b'q') => Some(PieceType::Queen),
+            Some(b'r') => Some(PieceType::Rook),
+            Some(b'b') => Some(PieceType::Bishop),
+            Some(b'n') => Some(PieceType::Knight),
+            Some(_) => return None,
+        };
+
+        Some(Move { from, to, promotion })
+    }
+}
+
+impl fmt::Display for Move {
+    /// Writes the move in UCI notation, e.g. "e7e8q"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.from, self.to)?;
+
+        if let Some(piece_type) = self.promotion {
+            let piece = Piece::new(piece_type, Color::Black);
+            write!(f, "{}", piece.to_char() as char)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the rank (0..8) where pawns of `color` promote
+fn last_rank(color: Color) -> usize {
+    match color {
+        Color::White => 7,
+        Color::Black => 0,
+    }
+}
+
+impl Board {
+    /// Returns every legal move of the side to move
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let Some(king) = self.king_square(self.turn) else {
+            return Vec::new();
+        };
+
+        let mut moves = self.pseudo_legal_moves();
+        moves.retain(|&mv| {
+            let mut after = self.clone();
+            after.make_move(mv);
+
+            // The king may have moved (when castling not necessarily to `mv.to`),
+            // the rest of the pieces only matter if they stopped a check
+            let our_king = if mv.from.index() == king { after.king_square(self.turn).unwrap() } else { king };
+            !after.is_attacked(our_king, !self.turn)
+        });
+        moves
+    }
+
+    /// Returns whether the side to move is in check
+    pub fn is_check(&self) -> bool {
+        !self.checkers().is_empty()
+    }
+
+    /// Returns the moves that follow the piece rules, including the ones that leave
+    /// the own king in check
+    fn pseudo_legal_moves(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        let occupied = self.occupied();
+
+        for from in squares(self.colors[self.turn as usize]) {
+            let Some(piece) = self.squares[from] else {continue};
+
+            let targets = match piece.piece_type {
+                PieceType::Pawn => {
+                    self.add_pawn_moves(from, &mut moves);
+                    continue;
+                },
+                PieceType::Knight => KNIGHT_ATTACKS[from],
+                PieceType::King => {
+                    self.add_castling_moves(from, &mut moves);
+                    KING_ATTACKS[from]
+                },
+                PieceType::Rook => rook_attacks(from, occupied),
+                PieceType::Bishop => bishop_attacks(from, occupied),
+                PieceType::Queen => rook_attacks(from, occupied) | bishop_attacks(from, occupied),
+            };
+
+            // Every attacked square that is empty or holds an enemy piece
+            for to in squares(targets & !self.colors[self.turn as usize]) {
+                moves.push(Move::new(Square(from as u8), Square(to as u8), None));
+            }
+        }
+
+        moves
+    }
+
+    /// Returns whether `index` holds a piece of the side not to move
+    fn is_enemy(&self, index: usize) -> bool {
+        matches!(&self.squares[index], Some(piece) if piece.color != self.turn)
+    }
+
+    /// Adds a move from `from` to `to`, or all four promotions if a pawn reaches the last rank
+    fn add_pawn_move(&self, from: usize, to: usize, moves: &mut Vec<Move>) {
+        let (from, to) = (Square(from as u8), Square(to as u8));
+
+        if to.rank() as usize == last_rank(self.turn) {
+            for piece_type in PROMOTIONS {
+                moves.push(Move::new(from, to, Some(piece_type)));
+            }
+        } else {
+            moves.push(Move::new(from, to, None));
+        }
+    }
+
+    fn add_pawn_moves(&self, from: usize, moves: &mut Vec<Move>) {
+        let (forward, start_rank) = match self.turn {
+            Color::White => (1, 1),
+            Color::Black => (-1, 6),
+        };
+
+        if let Some(to) = offset(from, (0, forward))
+            && self.squares[to].is_none() {
+            self.add_pawn_move(from, to, moves);
+
+            let (_, rank) = board_index_reverse(from);
+            if rank == start_rank
+                && let Some(to) = offset(to, (0, forward))
+                && self.squares[to].is_none() {
+                moves.push(Move::new(Square(from as u8), Square(to as u8), None));
+            }
+        }
+
+        for file_delta in [-1, 1] {
+            let Some(to) = offset(from, (file_delta, forward)) else {continue};
+
+            if self.is_enemy(to) || self.en_passant == Some(to) {
+                self.add_pawn_move(from, to, moves);
+            }
+        }
+    }
+
+    /// Adds castling moves for the king on `from`. The squares the king and the rook cross
+    /// or land on must be empty apart from the two of them, and the king may not castle out
+    /// of, through or into check. The king ends on the g- or c-file and the rook next to it
+    /// on the f- or d-file, also in Chess960.
+    ///
+    /// With the standard castling files the move is written as the king moving two squares,
+    /// otherwise as the king taking its own rook (as UCI does for Chess960)
+    fn add_castling_moves(&self, from: usize, moves: &mut Vec<Move>) {
+        let files = self.castling_files[self.turn as usize];
+        let rank = home_rank(self.turn);
+        if from != board_index(files.king as usize, rank) {
+            return;
+        }
+
+        let (kingside, queenside) = match self.turn {
+            Color::White => (self.castling.white_kingside, self.castling.white_queenside),
+            Color::Black => (self.castling.black_kingside, self.castling.black_queenside),
+        };
+
+        // (allowed, rook file, file the king ends on, file the rook ends on)
+        for (allowed, rook_file, king_to, rook_to) in [
+            (kingside, files.kingside_rook as usize, 6, 5),
+            (queenside, files.queenside_rook as usize, 2, 3),
+        ] {
+            if !allowed {continue;}
+            let king_file = files.king as usize;
+
+            let low = king_file.min(rook_file).min(king_to).min(rook_to);
+            let high = king_file.max(rook_file).max(king_to).max(rook_to);
+            let is_blocked = (low..=high)
+                .any(|file| file != king_file && file != rook_file && self.squares[board_index(file, rank)].is_some());
+            if is_blocked {continue;}
+
+            let is_passing_check = (king_file.min(king_to)..=king_file.max(king_to))
+                .any(|file| self.is_attacked(board_index(file, rank), !self.turn));
+            if is_passing_check {continue;}
+
+            let to_file = if files == CastlingFiles::STANDARD { king_to } else { rook_file };
+            moves.push(Move::new(Square(from as u8), Square(board_index(to_file, rank) as u8), None));
+        }
+    }
+
+    /// Plays `mv` and updates castling rights, the en passant square, the clocks
+    /// and the side to move. `mv` has to be legal in this position
+    pub fn make_move(&mut self, mv: Move) {
+        let (from, to) = (mv.from.index(), mv.to.index());
+        let piece = self.take(from).expect("no piece on the from square of the move");
+        let (from_file, from_rank) = board_index_reverse(from);
+        let (to_file, _) = board_index_reverse(to);
+
+        let mut is_capture = self.squares[to].is_some();
+
+        let is_castling = piece.piece_type == PieceType::King
+            && (from_file.abs_diff(to_file) == 2 || self.squares[to] == Some(Piece::new(PieceType::Rook, piece.color)));
+
+        if is_castling {
+            let files = self.castling_files[piece.color as usize];
+            let (rook_from, king_to, rook_to) = if to_file > from_file {
+                (files.kingside_rook, 6, 5)
+            } else {
+                (files.queenside_rook, 2, 3)
+            };
+
+            // In Chess960 the king and the rook can land on each other's squares,
+            // so both leave the board before either is put back
+            let rook = self.take(board_index(rook_from as usize, from_rank));
+            self.put(board_index(king_to, from_rank), Some(piece));
+            self.put(board_index(rook_to, from_rank), rook);
+            is_capture = false;
+        } else {
+            if piece.piece_type == PieceType::Pawn && from_file != to_file && self.squares[to].is_none() {
+                // En passant, the captured pawn stands next to the pawn that takes it
+                self.take(board_index(to_file, from_rank));
+                is_capture = true;
+            }
+
+            self.put(to, Some(match mv.promotion {
+                Some(piece_type) => Piece::new(piece_type, piece.color),
+                None => piece,
+            }));
+        }
+
+        self.en_passant = None;
+        if piece.piece_type == PieceType::Pawn && mv.from.rank().abs_diff(mv.to.rank()) == 2 {
+            self.en_passant = Some((from + to) / 2);
+        }
+
+        self.update_castling_rights(from, to);
+
+        if piece.piece_type == PieceType::Pawn || is_capture {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if self.turn == Color::Black {
+            self.fullmove_number += 1;
+        }
+        self.turn = !self.turn;
+    }
+
+    /// Drops the castling rights of a king or rook that moves away from or is captured on
+    /// its original square
+    fn update_castling_rights(&mut self, from: usize, to: usize) {
+        for square in [from, to] {
+            let (file, rank) = board_index_reverse(square);
+            let color = match rank {
+                0 => Color::White,
+                7 => Color::Black,
+                _ => continue,
+            };
+            let files = self.castling_files[color as usize];
+            let (kingside, queenside) = match color {
+                Color::White => (&mut self.castling.white_kingside, &mut self.castling.white_queenside),
+                Color::Black => (&mut self.castling.black_kingside, &mut self.castling.black_queenside),
+            };
+
+            let file = file as u8;
+            if file == files.king {
+                *kingside = false;
+                *queenside = false;
+            }
+            if file == files.kingside_rook {
+                *kingside = false;
+            }
+            if file == files.queenside_rook {
+                *queenside = false;
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::castling::home_rank;
+use crate::{board_index, Board, Color, PieceType, N_SQUARES};
+
+/// The classic handicaps, given by removing material from the start position
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Odds {
+    /// The f-pawn, and the other side moves first
+    PawnAndMove,
+    /// The queen's knight
+    Knight,
+    /// The queen's rook, which also takes away castling on that side
+    Rook,
+    /// The queen
+    Queen,
+}
+
+/// Returns the usual value of a piece in pawns, 0 for the king
+fn value(piece_type: PieceType) -> u32 {
+    match piece_type {
+        PieceType::Pawn => 1,
+        PieceType::Knight | PieceType::Bishop => 3,
+        PieceType::Rook => 5,
+        PieceType::Queen => 9,
+        PieceType::King => 0,
+    }
+}
+
+impl Odds {
+    /// Returns the file of the piece that is removed
+    fn file(self) -> usize {
+        match self {
+            Odds::PawnAndMove => 5,
+            Odds::Knight => 1,
+            Odds::Rook => 0,
+            Odds::Queen => 3,
+        }
+    }
+
+    /// Returns the square of the removed piece when `giver` gives the odds
+    fn square(self, giver: Color) -> usize {
+        let rank = match (self, giver) {
+            (Odds::PawnAndMove, Color::White) => 1,
+            (Odds::PawnAndMove, Color::Black) => 6,
+            _ => home_rank(giver),
+        };
+        board_index(self.file(), rank)
+    }
+}
+
+impl Board {
+    /// Returns the start position with `giver` giving `odds`. Castling rights are dropped
+    /// where the rook is gone, and for pawn and move the other side is to move
+    pub fn odds(odds: Odds, giver: Color) -> Board {
+        let mut board = Board::start_position();
+        board.take(odds.square(giver));
+
+        if odds == Odds::PawnAndMove {
+            board.turn = !giver;
+        }
+        board.castling = board.supported_castling();
+
+        board
+    }
+
+    /// Returns the start position with pieces of `giver` removed at random, worth at most
+    /// `budget` pawns in total (knight and bishop 3, rook 5, queen 9). Pieces are tried in
+    /// a random order and removed while they fit, so no further piece would fit afterwards.
+    /// Castling rights are dropped where the rook is gone
+    pub fn random_odds<R: Rng + ?Sized>(rng: &mut R, budget: u32, giver: Color) -> Board {
+        let mut board = Board::start_position();
+
+        let mut squares: Vec<usize> = (0..N_SQUARES)
+            .filter(|&square| matches!(board.squares[square], Some(piece) if piece.color == giver && piece.piece_type != PieceType::King))
+            .collect();
+        squares.shuffle(rng);
+
+        let mut left = budget;
+        for square in squares {
+            let cost = value(board.squares[square].unwrap().piece_type);
+            if cost <= left {
+                board.take(square);
+                left -= cost;
+            }
+        }
+        board.castling = board.supported_castling();
+
+        board
+    }
+}
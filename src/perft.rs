@@ -0,0 +1,39 @@
+use crate::{Board, Move};
+
+/// Counts the leaf nodes of the legal move tree of `board` down to `depth` plies
+pub fn perft(board: &Board, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let moves = board.legal_moves();
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+
+    moves.into_iter()
+        .map(|mv| {
+            let mut after = board.clone();
+            after.make_move(mv);
+            perft(&after, depth - 1)
+        })
+        .sum()
+}
+
+/// Same as `perft`, but returns the count below every legal move separately,
+/// which helps to find the move where two move generators disagree.
+/// Empty at depth 0, where no move is played
+pub fn divide(board: &Board, depth: u32) -> Vec<(Move, u64)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+
+    board.legal_moves()
+        .into_iter()
+        .map(|mv| {
+            let mut after = board.clone();
+            after.make_move(mv);
+            (mv, perft(&after, depth - 1))
+        })
+        .collect()
+}
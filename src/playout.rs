@@ -0,0 +1,102 @@
+use rand::seq::IndexedRandom;
+use rand::Rng;
+
+use crate::bitboard::bit;
+use crate::{Board, Move, PieceType};
+
+/// d4, e4, d5 and e5
+const CENTER: u64 = 0x0000_0018_1800_0000;
+
+/// How `Board::playout` picks its moves. Every legal move has weight 1, plus each bonus
+/// that applies to it, so the default with all bonuses 0 picks uniformly.
+/// A bonus may be negative to make moves rarer. Weights below 0 count as 0, and when
+/// every legal move has weight 0 one of them is picked uniformly
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct MoveWeights {
+    /// Added for captures, en passant included
+    pub capture: f64,
+    /// Added for moves that give check
+    pub check: f64,
+    /// Added for moves to d4, e4, d5 or e5
+    pub center: f64,
+}
+
+/// Settings for `Board::playout`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PlayoutConfig {
+    /// Fewest plies to play, unless the game ends before
+    pub min_plies: u32,
+    /// Most plies to play. The number is drawn uniformly from `min_plies..=max_plies`
+    pub max_plies: u32,
+    pub weights: MoveWeights,
+}
+
+impl PlayoutConfig {
+    /// Plays exactly `plies` plies, unless the game ends before
+    pub fn fixed(plies: u32) -> Self {
+        PlayoutConfig { min_plies: plies, max_plies: plies, weights: MoveWeights::default() }
+    }
+}
+
+impl Default for PlayoutConfig {
+    fn default() -> Self {
+        PlayoutConfig { min_plies: 10, max_plies: 40, weights: MoveWeights::default() }
+    }
+}
+
+impl Board {
+    /// Plays random legal moves from this position and returns where they lead.
+    /// Stops early at checkmate or stalemate. Castling rights, the en passant square
+    /// and the clocks are kept up to date by `make_move`
+    pub fn playout<R: Rng + ?Sized>(&self, rng: &mut R, config: &PlayoutConfig) -> Board {
+        assert!(config.min_plies <= config.max_plies, "min_plies is above max_plies");
+
+        let mut board = self.clone();
+        let plies = rng.random_range(config.min_plies..=config.max_plies);
+
+        for _ in 0..plies {
+            let moves = board.legal_moves();
+            if moves.is_empty() {break;}
+
+            let &mv = match moves.choose_weighted(rng, |&mv| board.move_weight(mv, &config.weights).max(0.0)) {
+                Ok(mv) => mv,
+                Err(_) => moves.choose(rng).expect("there is a legal move"),
+            };
+            board.make_move(mv);
+        }
+
+        board
+    }
+
+    /// Returns the weight of the legal move `mv` under `weights`
+    fn move_weight(&self, mv: Move, weights: &MoveWeights) -> f64 {
+        let mut weight = 1.0;
+
+        if weights.capture != 0.0 && self.is_capture(mv) {
+            weight += weights.capture;
+        }
+        if weights.check != 0.0 {
+            let mut after = self.clone();
+            after.make_move(mv);
+            if after.is_check() {
+                weight += weights.check;
+            }
+        }
+        if CENTER & bit(mv.to.index()) != 0 {
+            weight += weights.center;
+        }
+
+        weight
+    }
+
+    /// Returns whether the legal move `mv` takes a piece. Castling by taking the own rook
+    /// does not count
+    fn is_capture(&self, mv: Move) -> bool {
+        let Some(piece) = self.squares[mv.from.index()] else {return false};
+
+        match self.squares[mv.to.index()] {
+            Some(target) => target.color != piece.color,
+            None => self.en_passant == Some(mv.to.index()) && piece.piece_type == PieceType::Pawn,
+        }
+    }
+}
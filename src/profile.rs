@@ -0,0 +1,275 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rand::seq::IndexedRandom;
+use rand::Rng;
+
+use crate::bitboard::{squares, KING_ATTACKS, PAWN_RANKS};
+use crate::{
+    board_index_reverse, Board, ClockMode, Color, EnPassantMode, PieceCount, Piece, PieceType, RandomConfig, SideMaterial,
+};
+
+/// Presets for `Board::random` that resemble a phase of the game. Each one fixes the
+/// material, and places pawns, kings and pieces where they tend to stand in that phase
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Profile {
+    /// (Nearly) full material, pawns close to home, kings on the back rank,
+    /// mostly with castling rights
+    Opening,
+    /// Some pieces and pawns traded, kings sheltered behind their pawns
+    Middlegame,
+    /// At most one piece of each kind per side and a few pawns, active kings
+    Endgame,
+    /// Only kings and pawns
+    PawnEndgame,
+    /// Kings, pawns and a knight and/or a bishop per side
+    MinorPieceEndgame,
+}
+
+/// The name given to `Profile::from_str` does not belong to any profile
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownProfile(pub String);
+
+/// The pieces placed after the pawns, in the order of `SideMaterial::counts`
+const PIECES: [PieceType; 4] = [PieceType::Knight, PieceType::Bishop, PieceType::Rook, PieceType::Queen];
+
+/// Where pawns and kings go, shared by the profiles of one phase
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Opening,
+    Middlegame,
+    Endgame,
+}
+
+impl Profile {
+    pub const ALL: [Profile; 5] = [
+        Profile::Opening,
+        Profile::Middlegame,
+        Profile::Endgame,
+        Profile::PawnEndgame,
+        Profile::MinorPieceEndgame,
+    ];
+
+    /// Returns the name used on the command line, e.g. "pawn-endgame"
+    pub fn name(self) -> &'static str {
+        match self {
+            Profile::Opening => "opening",
+            Profile::Middlegame => "middlegame",
+            Profile::Endgame => "endgame",
+            Profile::PawnEndgame => "pawn-endgame",
+            Profile::MinorPieceEndgame => "minor-piece-endgame",
+        }
+    }
+
+    /// Returns the material each side gets, the same for both
+    pub fn material(self) -> SideMaterial {
+        let (pawns, knights, bishops, rooks, queens) = match self {
+            Profile::Opening => ((7, 8), (1, 2), (1, 2), (2, 2), (1, 1)),
+            Profile::Middlegame => ((4, 7), (0, 2), (0, 2), (1, 2), (0, 1)),
+            Profile::Endgame => ((0, 5), (0, 1), (0, 1), (0, 1), (0, 1)),
+            Profile::PawnEndgame => ((1, 6), (0, 0), (0, 0), (0, 0), (0, 0)),
+            Profile::MinorPieceEndgame => ((0, 5), (0, 1), (0, 1), (0, 0), (0, 0)),
+        };
+        let count = |(min, max)| PieceCount::between(min, max);
+
+        SideMaterial {
+            pawns: count(pawns),
+            knights: count(knights),
+            bishops: count(bishops),
+            rooks: count(rooks),
+            queens: count(queens),
+        }
+    }
+
+    /// Returns a config for `Board::random` with the material, castling, en passant
+    /// and clock settings of this profile
+    pub fn config(self) -> RandomConfig {
+        let castling_probability = match self {
+            Profile::Opening => 0.9,
+            Profile::Middlegame => 0.4,
+            _ => 0.0,
+        };
+        let fullmove_number = match self.phase() {
+            Phase::Endgame => ClockMode::Random,
+            _ => ClockMode::Derived,
+        };
+
+        RandomConfig {
+            white: self.material(),
+            black: self.material(),
+            castling_probability,
+            en_passant: EnPassantMode::Legal,
+            en_passant_probability: 0.25,
+            halfmove_clock: ClockMode::Derived,
+            fullmove_number,
+            profile: Some(self),
+            ..RandomConfig::default()
+        }
+    }
+
+    fn phase(self) -> Phase {
+        match self {
+            Profile::Opening => Phase::Opening,
+            Profile::Middlegame => Phase::Middlegame,
+            Profile::Endgame | Profile::PawnEndgame | Profile::MinorPieceEndgame => Phase::Endgame,
+        }
+    }
+
+    /// Places the kings and the material of `config` like `Board::place_pieces`, but
+    /// every square is drawn with the weights of this profile instead of uniformly.
+    /// In a minor piece endgame a side without a knight or a bishop draws its material
+    /// again, as long as its ranges allow one
+    pub(crate) fn place_pieces<R: Rng + ?Sized>(self, rng: &mut R, config: &RandomConfig) -> Board {
+        let mut board = Board::new();
+        board.turn = if rng.random_bool(0.5) { Color::White } else { Color::Black };
+
+        for color in [Color::White, Color::Black] {
+            // The second king can go anywhere but on or next to the first
+            let taken = squares(board.occupied()).fold(board.occupied(), |taken, square| taken | KING_ATTACKS[square]);
+            let square = choose_square(rng, !taken, |square| self.king_weight(color, square));
+            board.put(square, Some(Piece::new(PieceType::King, color)));
+        }
+
+        let sides = [(Color::White, &config.white), (Color::Black, &config.black)].map(|(color, material)| {
+            let mut counts = draw_counts(rng, material);
+            let can_have_minor = material.knights.max > 0 || material.bishops.max > 0;
+            while self == Profile::MinorPieceEndgame && can_have_minor && counts[1] + counts[2] == 0 {
+                counts = draw_counts(rng, material);
+            }
+            (color, counts)
+        });
+
+        for (color, counts) in sides {
+            for _ in 0..counts[0] {
+                let square = choose_square(rng, PAWN_RANKS & !board.occupied(), |square| self.pawn_weight(&board, color, square));
+                board.put(square, Some(Piece::new(PieceType::Pawn, color)));
+            }
+        }
+
+        for (color, counts) in sides {
+            for (piece_type, &count) in PIECES.into_iter().zip(&counts[1..]) {
+                for _ in 0..count {
+                    let square = choose_square(rng, !board.occupied(), |square| self.piece_weight(color, square));
+                    board.put(square, Some(Piece::new(piece_type, color)));
+                }
+            }
+        }
+
+        board
+    }
+
+    /// Returns how likely the king of `color` is to stand on `square`: at home early on,
+    /// tucked into a corner in the middlegame and in the center in the endgame
+    fn king_weight(self, color: Color, square: usize) -> f64 {
+        let (file, rank) = relative(color, square);
+        let centrality = file.min(7 - file) + rank.min(7 - rank);
+
+        match (self.phase(), rank) {
+            (Phase::Opening, 0) if file == 4 => 12.0,
+            (Phase::Opening, 0) if file == 2 || file == 6 => 6.0,
+            (Phase::Opening, 0) => 1.0,
+            (Phase::Opening, 1) => 0.3,
+            (Phase::Opening, _) => 0.01,
+            (Phase::Middlegame, 0) if file <= 2 || file >= 6 => 6.0,
+            (Phase::Middlegame, 0) => 2.0,
+            (Phase::Middlegame, 1) => 1.0,
+            (Phase::Middlegame, _) => 0.05,
+            (Phase::Endgame, _) => 1.0 + centrality as f64,
+        }
+    }
+
+    /// Returns how likely a knight, bishop, rook or queen of `color` is to stand on `square`:
+    /// mostly on its own half before the endgame, anywhere after
+    fn piece_weight(self, color: Color, square: usize) -> f64 {
+        let ranks = match self.phase() {
+            Phase::Opening => [8.0, 4.0, 2.0, 1.0, 0.3, 0.2, 0.1, 0.1],
+            Phase::Middlegame => [3.0, 3.0, 2.5, 2.0, 1.5, 1.0, 0.7, 0.5],
+            Phase::Endgame => return 1.0,
+        };
+        ranks[relative(color, square).1]
+    }
+
+    /// Returns how likely the next pawn of `color` is to go to `square`. Pawns advance
+    /// further the later the phase, avoid files that already have a pawn of their color,
+    /// and before the endgame gather in front of their king
+    fn pawn_weight(self, board: &Board, color: Color, square: usize) -> f64 {
+        // Indexed by the rank counted from the side of `color`, 1 is the starting rank
+        let (ranks, doubled, shelter): ([f64; 8], f64, f64) = match self.phase() {
+            Phase::Opening => ([0.0, 10.0, 5.0, 1.0, 0.3, 0.1, 0.05, 0.0], 0.05, 2.0),
+            Phase::Middlegame => ([0.0, 6.0, 5.0, 3.0, 1.0, 0.4, 0.2, 0.0], 0.25, 4.0),
+            Phase::Endgame => ([0.0, 3.0, 3.0, 3.0, 2.5, 2.0, 1.5, 0.0], 0.4, 1.0),
+        };
+        let (file, rank) = relative(color, square);
+        let mut weight = ranks[rank];
+
+        let pawns = board.bitboard(Piece::new(PieceType::Pawn, color));
+        let on_file = squares(pawns).filter(|&pawn| relative(color, pawn).0 == file).count();
+        weight *= doubled.powi(on_file as i32);
+
+        if let Some(king) = board.king_square(color) {
+            let (king_file, king_rank) = relative(color, king);
+            if file.abs_diff(king_file) <= 1 && (king_rank + 1..=king_rank + 2).contains(&rank) {
+                weight *= shelter;
+            }
+        }
+
+        weight
+    }
+}
+
+/// Returns the file and the rank of `square` as seen from the side of `color`
+fn relative(color: Color, square: usize) -> (usize, usize) {
+    let (file, rank) = board_index_reverse(square);
+    match color {
+        Color::White => (file, rank),
+        Color::Black => (file, 7 - rank),
+    }
+}
+
+/// Draws how many pawns, knights, bishops, rooks and queens a side gets
+fn draw_counts<R: Rng + ?Sized>(rng: &mut R, material: &SideMaterial) -> [u8; 5] {
+    material.counts().map(|(_, count)| rng.random_range(count.min..=count.max))
+}
+
+/// Picks one of the squares in `free` with probability proportional to `weight`
+fn choose_square<R: Rng + ?Sized>(rng: &mut R, free: u64, weight: impl Fn(usize) -> f64) -> usize {
+    let candidates: Vec<usize> = squares(free).collect();
+    match candidates.choose_weighted(rng, |&square| weight(square)) {
+        Ok(&square) => square,
+        // Every free square has weight 0, so fall back to a uniform choice
+        Err(_) => *candidates.choose(rng).expect("no free square left"),
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Profile {
+    type Err = UnknownProfile;
+
+    /// Parses a name as returned by `Profile::name`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Profile::ALL.into_iter()
+            .find(|profile| profile.name() == s)
+            .ok_or_else(|| UnknownProfile(s.to_string()))
+    }
+}
+
+impl fmt::Display for UnknownProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = Profile::ALL.iter().map(|profile| profile.name()).collect();
+        write!(f, "unknown profile {:?}, expected one of {}", self.0, names.join(", "))
+    }
+}
+
+impl std::error::Error for UnknownProfile {}
+
+impl From<Profile> for RandomConfig {
+    /// Same as `profile.config()`
+    fn from(profile: Profile) -> Self {
+        profile.config()
+    }
+}
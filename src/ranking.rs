@@ -0,0 +1,188 @@
+use rand::Rng;
+
+use crate::{board_index, Board, Color, GenerateError, Material, Piece, PieceType, Positions, N_SQUARES, PAWN_SQUARES};
+
+/// Binomial coefficients C(n, k) for n, k <= 64
+static BINOMIALS: [[u128; N_SQUARES + 1]; N_SQUARES + 1] = {
+    let mut table = [[0; N_SQUARES + 1]; N_SQUARES + 1];
+    let mut n = 0;
+    while n <= N_SQUARES {
+        table[n][0] = 1;
+        let mut k = 1;
+        while k <= n {
+            table[n][k] = table[n - 1][k - 1] + table[n - 1][k];
+            k += 1;
+        }
+        n += 1;
+    }
+    table
+};
+
+/// Pieces of one type and color, which are placed together
+struct Group {
+    piece: Piece,
+    count: usize,
+    /// How many squares are left for this group after the groups before it
+    free: usize,
+}
+
+/// Numbers all placements of some exact material, for both sides to move,
+/// with 0..size. Pawns are only placed from the second to the seventh rank;
+/// apart from that the placements can be illegal.
+///
+/// `rank` and `unrank` are inverse to each other, so a position can be shared as its index.
+/// Castling rights, en passant square and clocks are not part of the index
+pub struct PositionIndex {
+    material: Material,
+    /// Pawn groups first, then the rest
+    groups: Vec<Group>,
+    size: u128,
+}
+
+impl PositionIndex {
+    /// Creates the index, or returns None if the material does not fit on the board
+    /// or there are more placements than fit in a u128 (roughly beyond 25 pieces)
+    pub fn new(material: &Material) -> Option<Self> {
+        let mut groups: Vec<Group> = Vec::new();
+        for piece in material.pieces() {
+            match groups.last_mut() {
+                Some(group) if group.piece == piece => group.count += 1,
+                _ => groups.push(Group { piece, count: 1, free: 0 }),
+            }
+        }
+        groups.sort_by_key(|group| group.piece.piece_type != PieceType::Pawn);
+
+        let mut size: u128 = 2;
+        let mut pawns = 0;
+        let mut pieces = 0;
+        for group in &mut groups {
+            group.free = match group.piece.piece_type {
+                PieceType::Pawn => PAWN_SQUARES.checked_sub(pawns)?,
+                _ => N_SQUARES.checked_sub(pieces)?,
+            };
+            if group.count > group.free {
+                return None;
+            }
+            size = size.checked_mul(BINOMIALS[group.free][group.count])?;
+
+            if group.piece.piece_type == PieceType::Pawn {
+                pawns += group.count;
+            }
+            pieces += group.count;
+        }
+
+        Some(PositionIndex { material: *material, groups, size })
+    }
+
+    /// Returns the number of placements (times two for the side to move)
+    pub fn size(&self) -> u128 {
+        self.size
+    }
+
+    /// Returns the squares a group can use given the squares taken by the groups before it
+    fn free_squares(&self, group: &Group, taken: &[bool; N_SQUARES]) -> Vec<usize> {
+        let range = match group.piece.piece_type {
+            PieceType::Pawn => board_index(0, 1)..board_index(0, 7),
+            _ => 0..N_SQUARES,
+        };
+        range.filter(|&square| !taken[square]).collect()
+    }
+
+    /// Returns the index of the board, or None if its material is different or a pawn
+    /// stands on the first or last rank
+    pub fn rank(&self, board: &Board) -> Option<u128> {
+        let mut taken = [false; N_SQUARES];
+        let mut index: u128 = 0;
+        let mut placed = 0;
+
+        for group in &self.groups {
+            let free = self.free_squares(group, &taken);
+
+            // The combinatorial number system: k sorted positions p_1 < ... < p_k among
+            // the free squares get the number C(p_1, 1) + ... + C(p_k, k)
+            let mut combination: u128 = 0;
+            let mut k = 0;
+            for (position, &square) in free.iter().enumerate() {
+                if board.squares[square] != Some(group.piece) {continue;}
+
+                k += 1;
+                combination += BINOMIALS[position][k];
+                taken[square] = true;
+            }
+            if k != group.count {
+                return None;
+            }
+            placed += k;
+
+            index = index * BINOMIALS[group.free][group.count] + combination;
+        }
+
+        // Anything else on the board means the material does not match
+        if board.squares.iter().flatten().count() != placed {
+            return None;
+        }
+
+        Some(2 * index + (board.turn == Color::Black) as u128)
+    }
+
+    /// Returns the board with this index, or None if the index is not below `size`
+    pub fn unrank(&self, index: u128) -> Option<Board> {
+        if index >= self.size {
+            return None;
+        }
+
+        let mut board = Board::new();
+        board.turn = if index.is_multiple_of(2) { Color::White } else { Color::Black };
+
+        // The first group is the most significant digit, so peel the digits off from the last group
+        let mut remaining = index / 2;
+        let mut combinations = vec![0; self.groups.len()];
+        for (i, group) in self.groups.iter().enumerate().rev() {
+            let radix = BINOMIALS[group.free][group.count];
+            combinations[i] = remaining % radix;
+            remaining /= radix;
+        }
+
+        let mut taken = [false; N_SQUARES];
+        for (group, mut combination) in self.groups.iter().zip(combinations) {
+            let free = self.free_squares(group, &taken);
+
+            // Undo the combinatorial number system, largest position first
+            let mut position = free.len();
+            for k in (1..=group.count).rev() {
+                position -= 1;
+                while BINOMIALS[position][k] > combination {
+                    position -= 1;
+                }
+                combination -= BINOMIALS[position][k];
+
+                let square = free[position];
+                board.put(square, Some(group.piece));
+                taken[square] = true;
+            }
+        }
+
+        Some(board)
+    }
+
+    /// Counts the legal positions with this material by going through all of them,
+    /// see `Positions`. Only practical for a handful of pieces
+    pub fn count_legal(&self) -> u128 {
+        Positions::new(&self.material, false).count() as u128
+    }
+
+    /// Returns a legal board, every legal board with this material being equally likely.
+    ///
+    /// Draws an index uniformly from 0..size until it belongs to a legal board. As every
+    /// legal board has exactly one index, each of them is hit with the same chance
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R, max_attempts: u32) -> Result<Board, GenerateError> {
+        for _ in 0..max_attempts {
+            let board = self.unrank(rng.random_range(0..self.size)).unwrap();
+            if board.is_legal() {
+                return Ok(board);
+            }
+        }
+
+        Err(GenerateError::AttemptsExhausted { attempts: max_attempts })
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt;
+
+use crate::bitboard::squares;
+use crate::castling::home_rank;
+use crate::{board_index, board_index_reverse, Board, Color, Piece, PieceType};
+
+/// Dark squares, a1 = 0 is dark
+const DARK_SQUARES: u64 = 0xAA55_AA55_AA55_AA55;
+
+/// Why a position can not arise from the standard start position, found by counting.
+/// Passing every check does not prove that the position is reachable
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Unreachable {
+    /// The pawns left plus the pieces that must have been promoted are more than eight.
+    /// A piece is promoted when a side has more of its type than at the start, bishops
+    /// counted per square color, or when its bishop could not have left its square
+    TooManyPromotions { color: Color, pawns: u8, promoted: u8 },
+    /// The pawns can not have come from different files of their starting rank,
+    /// as a pawn can change its file only by capturing on its way forward
+    PawnFiles { color: Color },
+    /// Getting the pawns to their files takes more captures than the enemy has lost
+    TooManyPawnCaptures { color: Color, captures: u8, missing: u8 },
+    /// A castling right without the king and the rook on their original squares
+    CastlingWithMovedPiece { color: Color },
+}
+
+/// Returns "white" or "black"
+fn name(color: Color) -> &'static str {
+    match color {
+        Color::White => "white",
+        Color::Black => "black",
+    }
+}
+
+impl fmt::Display for Unreachable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Unreachable::TooManyPromotions { color, pawns, promoted } => write!(
+                f, "{} has {} pawns and at least {} promoted pieces, but only 8 pawns to start with",
+                name(color), pawns, promoted),
+            Unreachable::PawnFiles { color } => write!(
+                f, "the {} pawns can not have come from different files", name(color)),
+            Unreachable::TooManyPawnCaptures { color, captures, missing } => write!(
+                f, "the {} pawns need at least {} captures, but only {} enemy pieces are missing",
+                name(color), captures, missing),
+            Unreachable::CastlingWithMovedPiece { color } => write!(
+                f, "{} can castle, but its king or rook has left its square", name(color)),
+        }
+    }
+}
+
+impl std::error::Error for Unreachable {}
+
+impl Board {
+    /// Runs cheap counting checks that some positions allowed by `is_legal` fail, such as
+    /// ten queens next to eight pawns. Assumes the standard start position, so Chess960
+    /// bishops are taken to start on the c- and f-file too
+    pub fn check_reachable(&self) -> Result<(), Unreachable> {
+        let counts = self.piece_counts();
+
+        for color in [Color::White, Color::Black] {
+            let pawns = counts[color as usize][PieceType::Pawn as usize];
+            let promoted = self.min_promoted(color);
+            if pawns + promoted > 8 {
+                return Err(Unreachable::TooManyPromotions { color, pawns, promoted });
+            }
+
+            let captures = self.min_pawn_captures(color).ok_or(Unreachable::PawnFiles { color })?;
+            // More than sixteen pieces fail the promotion check of their side, which may
+            // not have run yet
+            let missing = 16u8.saturating_sub(counts[!color as usize].iter().sum::<u8>());
+            if captures > missing {
+                return Err(Unreachable::TooManyPawnCaptures { color, captures, missing });
+            }
+        }
+
+        let (rights, supported) = (self.castling, self.supported_castling());
+        for (color, moved) in [
+            (Color::White, rights.white_kingside && !supported.white_kingside || rights.white_queenside && !supported.white_queenside),
+            (Color::Black, rights.black_kingside && !supported.black_kingside || rights.black_queenside && !supported.black_queenside),
+        ] {
+            if moved {
+                return Err(Unreachable::CastlingWithMovedPiece { color });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether `check_reachable` finds nothing wrong
+    pub fn is_reachable(&self) -> bool {
+        self.check_reachable().is_ok()
+    }
+
+    /// Returns the fewest pieces of `color` that must have come from promotions
+    fn min_promoted(&self, color: Color) -> u8 {
+        let count = |piece_type: PieceType| self.bitboard(Piece::new(piece_type, color)).count_ones() as u8;
+        let mut promoted = count(PieceType::Queen).saturating_sub(1)
+            + count(PieceType::Rook).saturating_sub(2)
+            + count(PieceType::Knight).saturating_sub(2);
+
+        // Each bishop starts on the c- or f-file of the home rank. With the pawns diagonally
+        // in front of it still at home it never left, so a bishop of its square color
+        // elsewhere was promoted
+        let bishops = self.bitboard(Piece::new(PieceType::Bishop, color));
+        let pawn = Some(Piece::new(PieceType::Pawn, color));
+        let (home, pawn_rank) = (home_rank(color), home_rank(color).abs_diff(1));
+        for file in [2, 5] {
+            let start = board_index(file, home);
+            let same_color = if DARK_SQUARES & (1 << start) != 0 { DARK_SQUARES } else { !DARK_SQUARES };
+            let on_color = (bishops & same_color).count_ones() as u8;
+
+            let is_shut_in = self.squares[board_index(file - 1, pawn_rank)] == pawn
+                && self.squares[board_index(file + 1, pawn_rank)] == pawn;
+            let original = if is_shut_in { (bishops & (1 << start) != 0) as u8 } else { 1 };
+            promoted += on_color.saturating_sub(original);
+        }
+
+        promoted
+    }
+
+    /// Returns the fewest captures the pawns of `color` need to reach their files, each
+    /// starting on a different file, or None if they can not. A pawn moves one file
+    /// per capture and makes each capture on a different rank, so a pawn `n` ranks ahead
+    /// of its starting rank is at most `n` files away from where it started
+    fn min_pawn_captures(&self, color: Color) -> Option<u8> {
+        let pawns: Vec<(usize, usize)> = squares(self.bitboard(Piece::new(PieceType::Pawn, color)))
+            .map(|square| {
+                let (file, rank) = board_index_reverse(square);
+                (file, rank.abs_diff(home_rank(color)).saturating_sub(1))
+            })
+            .collect();
+        if pawns.len() > 8 {
+            return None;
+        }
+
+        // `best[files]` is the fewest captures that put the first pawns on the set of
+        // starting files `files`, one bit per file
+        let mut best = [None::<u8>; 256];
+        best[0] = Some(0);
+        for (i, &(file, advanced)) in pawns.iter().enumerate() {
+            let mut next = [None::<u8>; 256];
+            for files in (0..256usize).filter(|files| files.count_ones() as usize == i) {
+                let Some(captures) = best[files] else {continue};
+
+                for start in (0..8usize).filter(|&start| files & (1 << start) == 0 && start.abs_diff(file) <= advanced) {
+                    let total = captures + start.abs_diff(file) as u8;
+                    let entry = &mut next[files | (1 << start)];
+                    *entry = Some(entry.map_or(total, |other| other.min(total)));
+                }
+            }
+            best = next;
+        }
+
+        best.into_iter().flatten().min()
+    }
+}
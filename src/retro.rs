@@ -0,0 +1,302 @@
+use std::fmt;
+
+use crate::attacks::{aligned, is_slider, offset};
+use crate::bitboard::{bishop_attacks, rook_attacks, squares, KING_ATTACKS, KNIGHT_ATTACKS};
+use crate::castling::home_rank;
+use crate::{board_index, board_index_reverse, Board, CastlingFiles, Color, Move, Piece, PieceType, Square};
+
+/// Pieces a capture can take back, the king never being captured
+const UNCAPTURES: [PieceType; 5] = [PieceType::Pawn, PieceType::Knight, PieceType::Bishop, PieceType::Rook, PieceType::Queen];
+
+/// A move that leads to the position, found by taking it back
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UnMove {
+    /// The move as it was played in `previous`
+    pub mv: Move,
+    /// The piece it took, a pawn for en passant
+    pub captured: Option<PieceType>,
+    /// The position before the move. It has no en passant square unless the move
+    /// took en passant, the castling rights of the position after it (plus the right
+    /// used when the move castles) and the clocks of the position after it
+    pub previous: Board,
+}
+
+/// Why no move can have led to a position
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum RetroError {
+    /// The side that just moved left its own king in check
+    MoverInCheck,
+    /// More than two pieces give check, and one move uncovers at most one line
+    TooManyCheckers(usize),
+    /// Two pieces give check, but neither could have been uncovered by the other
+    /// as neither is a bishop, rook or queen
+    DoubleCheckWithoutSlider(PieceType, PieceType),
+    /// Two pieces give check along the same line through the king
+    AlignedDoubleCheck,
+    /// The en passant square is set, but no double pawn push can have been the last move
+    NoDoublePush,
+    /// The side to move is in check, but no move can have given it
+    NoCheckingMove,
+    /// Taking back any move of the side that just moved gives an illegal position
+    NoLastMove,
+}
+
+/// Returns the name of a piece type, e.g. "knight"
+fn name(piece_type: PieceType) -> &'static str {
+    match piece_type {
+        PieceType::Pawn => "pawn",
+        PieceType::Knight => "knight",
+        PieceType::Bishop => "bishop",
+        PieceType::Rook => "rook",
+        PieceType::Queen => "queen",
+        PieceType::King => "king",
+    }
+}
+
+impl fmt::Display for RetroError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RetroError::MoverInCheck => write!(f, "the side that just moved is in check"),
+            RetroError::TooManyCheckers(n) => write!(f, "check by {} pieces cannot arise", n),
+            RetroError::DoubleCheckWithoutSlider(a, b) if a == b => {
+                write!(f, "double check by two {}s cannot arise", name(a))
+            },
+            RetroError::DoubleCheckWithoutSlider(a, b) => {
+                write!(f, "double check by a {} and a {} cannot arise", name(a), name(b))
+            },
+            RetroError::AlignedDoubleCheck => write!(f, "double check along one line cannot arise"),
+            RetroError::NoDoublePush => write!(f, "no double pawn push can have left the en passant square"),
+            RetroError::NoCheckingMove => write!(f, "no move can have given the check"),
+            RetroError::NoLastMove => write!(f, "no move can have led to the position"),
+        }
+    }
+}
+
+impl std::error::Error for RetroError {}
+
+impl Board {
+    /// Returns every move of the side not to move that leads to this position from a legal
+    /// one, with captures and promotions taken back too. Each previous position is checked
+    /// by playing the move forward again. The clocks are not looked at
+    pub fn un_moves(&self) -> Vec<UnMove> {
+        let mut un_moves = Vec::new();
+        let mover = !self.turn;
+
+        // No move may leave the own king in check
+        if let Some(king) = self.king_square(mover)
+            && self.is_attacked(king, self.turn) {
+            return un_moves;
+        }
+
+        for to in squares(self.colors[mover as usize]) {
+            let piece = self.squares[to].unwrap();
+
+            if piece.piece_type == PieceType::King {
+                self.add_un_castlings(to, &mut un_moves);
+            }
+            if piece.piece_type == PieceType::Pawn {
+                self.add_pawn_un_moves(to, None, &mut un_moves);
+                continue;
+            }
+            if piece.piece_type != PieceType::King && board_index_reverse(to).1 == home_rank(self.turn) {
+                self.add_pawn_un_moves(to, Some(piece.piece_type), &mut un_moves);
+            }
+
+            let occupied = self.occupied();
+            let froms = match piece.piece_type {
+                PieceType::Knight => KNIGHT_ATTACKS[to],
+                PieceType::Bishop => bishop_attacks(to, occupied),
+                PieceType::Rook => rook_attacks(to, occupied),
+                PieceType::Queen => bishop_attacks(to, occupied) | rook_attacks(to, occupied),
+                PieceType::King => KING_ATTACKS[to],
+                PieceType::Pawn => unreachable!(),
+            };
+            for from in squares(froms & !occupied) {
+                self.add_un_move(from, to, piece, None, &mut un_moves);
+            }
+        }
+
+        un_moves
+    }
+
+    /// Returns one move that leads to this position, see `un_moves`, or why there is none
+    pub fn check_last_move(&self) -> Result<UnMove, RetroError> {
+        let mover = !self.turn;
+        if let Some(king) = self.king_square(mover)
+            && self.is_attacked(king, self.turn) {
+            return Err(RetroError::MoverInCheck);
+        }
+
+        let checkers = self.checkers();
+        match checkers[..] {
+            [a, b] => {
+                let piece_type = |index: usize| self.squares[index].unwrap().piece_type;
+                let (a_type, b_type) = (piece_type(a), piece_type(b));
+                if !is_slider(a_type) && !is_slider(b_type) {
+                    // In `PieceType` order, so the same pair always reads the same
+                    let (first, second) = if (a_type as usize) <= (b_type as usize) { (a_type, b_type) } else { (b_type, a_type) };
+                    return Err(RetroError::DoubleCheckWithoutSlider(first, second));
+                }
+                if aligned(self.king_square(self.turn).unwrap(), a, b) {
+                    return Err(RetroError::AlignedDoubleCheck);
+                }
+            },
+            [_, _, _, ..] => return Err(RetroError::TooManyCheckers(checkers.len())),
+            _ => {},
+        }
+
+        self.un_moves().into_iter().next().ok_or(match (self.en_passant, checkers.is_empty()) {
+            (Some(_), _) => RetroError::NoDoublePush,
+            (None, false) => RetroError::NoCheckingMove,
+            (None, true) => RetroError::NoLastMove,
+        })
+    }
+
+    /// Returns whether `check_last_move` finds a move
+    pub fn has_last_move(&self) -> bool {
+        self.check_last_move().is_ok()
+    }
+
+    /// Adds the pawn moves that end on `to`: pushes, captures and en passant. With
+    /// `promotion` the piece on `to` is what the pawn promoted to
+    fn add_pawn_un_moves(&self, to: usize, promotion: Option<PieceType>, un_moves: &mut Vec<UnMove>) {
+        let mover = !self.turn;
+        let back = match mover {
+            Color::White => -1,
+            Color::Black => 1,
+        };
+        let pawn = Piece::new(PieceType::Pawn, mover);
+
+        if let Some(from) = offset(to, (0, back))
+            && self.squares[from].is_none() {
+            self.add_un_move(from, to, pawn, promotion, un_moves);
+
+            // A double push from the starting rank, next to the home rank
+            if promotion.is_none()
+                && let Some(start) = offset(from, (0, back))
+                && self.squares[start].is_none()
+                && board_index_reverse(start).1.abs_diff(home_rank(mover)) == 1 {
+                self.add_un_move(start, to, pawn, None, un_moves);
+            }
+        }
+
+        for file_delta in [-1, 1] {
+            let Some(from) = offset(to, (file_delta, back)) else {continue};
+            if self.squares[from].is_some() {continue;}
+
+            self.add_uncaptures(from, to, pawn, promotion, un_moves);
+
+            // En passant: the taken pawn stood next to `from`, with `to` behind it
+            let Some(taken) = offset(to, (0, back)) else {continue};
+            let is_fifth_rank = board_index_reverse(from).1.abs_diff(home_rank(self.turn)) == 3;
+            if promotion.is_none() && is_fifth_rank && self.squares[taken].is_none() {
+                let mut previous = self.previous(from, to, pawn);
+                previous.put(taken, Some(Piece::new(PieceType::Pawn, self.turn)));
+                previous.en_passant = Some(to);
+                let mv = Move::new(Square(from as u8), Square(to as u8), None);
+                self.add_if_consistent(previous, mv, Some(PieceType::Pawn), un_moves);
+            }
+        }
+    }
+
+    /// Adds the move of `piece` from `from` to `to`, as a pawn promoting to `promotion`
+    /// if given, both without and (unless a pawn moves straight ahead) with a capture
+    fn add_un_move(&self, from: usize, to: usize, piece: Piece, promotion: Option<PieceType>, un_moves: &mut Vec<UnMove>) {
+        let mv = Move::new(Square(from as u8), Square(to as u8), promotion);
+        self.add_if_consistent(self.previous(from, to, piece), mv, None, un_moves);
+
+        // Pawns take diagonally, see `add_pawn_un_moves`
+        if piece.piece_type != PieceType::Pawn {
+            self.add_uncaptures(from, to, piece, promotion, un_moves);
+        }
+    }
+
+    /// Adds the captures from `from` to `to` of every piece type that can have stood on `to`
+    fn add_uncaptures(&self, from: usize, to: usize, piece: Piece, promotion: Option<PieceType>, un_moves: &mut Vec<UnMove>) {
+        // The side to move never has more than sixteen pieces
+        if self.colors[self.turn as usize].count_ones() >= 16 {
+            return;
+        }
+
+        let (_, rank) = board_index_reverse(to);
+        for captured in UNCAPTURES {
+            if captured == PieceType::Pawn && (rank == 0 || rank == 7) {continue;}
+
+            let mut previous = self.previous(from, to, piece);
+            previous.put(to, Some(Piece::new(captured, self.turn)));
+            let mv = Move::new(Square(from as u8), Square(to as u8), promotion);
+            self.add_if_consistent(previous, mv, Some(captured), un_moves);
+        }
+    }
+
+    /// Adds the castling moves that end with the king on `king`
+    fn add_un_castlings(&self, king: usize, un_moves: &mut Vec<UnMove>) {
+        let mover = !self.turn;
+        let rank = home_rank(mover);
+        let files = self.castling_files[mover as usize];
+
+        // (rook file, file the king ends on, file the rook ends on, kingside)
+        for (rook_file, king_to, rook_to, kingside) in [
+            (files.kingside_rook as usize, 6, 5, true),
+            (files.queenside_rook as usize, 2, 3, false),
+        ] {
+            let rook = Piece::new(PieceType::Rook, mover);
+            if king != board_index(king_to, rank) || self.squares[board_index(rook_to, rank)] != Some(rook) {continue;}
+
+            let mut previous = self.clone();
+            previous.take(king);
+            previous.take(board_index(rook_to, rank));
+            let (king_from, rook_from) = (board_index(files.king as usize, rank), board_index(rook_file, rank));
+            if previous.squares[king_from].is_some() || previous.squares[rook_from].is_some() {continue;}
+
+            previous.put(king_from, Some(Piece::new(PieceType::King, mover)));
+            previous.put(rook_from, Some(rook));
+            previous.turn = mover;
+            previous.en_passant = None;
+            match (mover, kingside) {
+                (Color::White, true) => previous.castling.white_kingside = true,
+                (Color::White, false) => previous.castling.white_queenside = true,
+                (Color::Black, true) => previous.castling.black_kingside = true,
+                (Color::Black, false) => previous.castling.black_queenside = true,
+            }
+
+            // Unlike the other moves, castling has conditions beyond the squares it crosses
+            let to_file = if files == CastlingFiles::STANDARD { king_to } else { rook_file };
+            let mv = Move::new(Square(king_from as u8), Square(board_index(to_file, rank) as u8), None);
+            if previous.legal_moves().contains(&mv) {
+                self.add_if_consistent(previous, mv, None, un_moves);
+            }
+        }
+    }
+
+    /// Returns this position with `piece` moved back from `to` to `from`, the side not
+    /// to move to move and no en passant square
+    fn previous(&self, from: usize, to: usize, piece: Piece) -> Board {
+        let mut previous = self.clone();
+        previous.take(to);
+        previous.put(from, Some(piece));
+        previous.turn = !self.turn;
+        previous.en_passant = None;
+        previous
+    }
+
+    /// Adds the un-move if `previous` is legal and playing `mv` in it gives this position
+    /// back, with the same castling rights and the en passant square if set. The squares
+    /// `mv` crosses are empty here, so they were empty before, and it does not leave
+    /// the king in check here, so it was legal
+    fn add_if_consistent(&self, previous: Board, mv: Move, captured: Option<PieceType>, un_moves: &mut Vec<UnMove>) {
+        if !previous.is_legal() {
+            return;
+        }
+
+        let mut after = previous.clone();
+        after.make_move(mv);
+        let is_same = after.squares == self.squares
+            && after.castling == self.castling
+            && (self.en_passant.is_none() || after.en_passant == self.en_passant);
+
+        if is_same {
+            un_moves.push(UnMove { mv, captured, previous });
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+
+use rand::Rng;
+
+use crate::{board_index_reverse, Board, Color, GenerateError, RandomConfig, N_SQUARES};
+
+/// Keys for 12 pieces on 64 squares, then black to move, the four castling rights
+/// and the eight en passant files
+const N_KEYS: usize = 12 * N_SQUARES + 1 + 4 + 8;
+const SIDE_KEY: usize = 12 * N_SQUARES;
+const CASTLING_KEYS: usize = SIDE_KEY + 1;
+const EN_PASSANT_KEYS: usize = CASTLING_KEYS + 4;
+
+/// Fixed pseudo-random keys from splitmix64, so hashes stay the same between runs
+static KEYS: [u64; N_KEYS] = {
+    let mut keys = [0; N_KEYS];
+    let mut state: u64 = 0x5EED_F0E5_2024_C0DE;
+
+    let mut i = 0;
+    while i < N_KEYS {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        keys[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    keys
+};
+
+impl Board {
+    /// Returns the Zobrist hash of the position: the pieces, the side to move, the castling
+    /// rights and the file of the en passant square. The clocks are not part of it.
+    /// The keys are fixed, so a position always has the same hash
+    pub fn zobrist_hash(&self) -> u64 {
+        let mut hash = 0;
+
+        for (square, piece) in self.squares.iter().enumerate() {
+            let Some(piece) = piece else {continue};
+
+            let piece_index = piece.color as usize * 6 + piece.piece_type as usize;
+            hash ^= KEYS[piece_index * N_SQUARES + square];
+        }
+
+        if self.turn == Color::Black {
+            hash ^= KEYS[SIDE_KEY];
+        }
+
+        let castling = self.castling;
+        for (i, allowed) in [
+            castling.white_kingside,
+            castling.white_queenside,
+            castling.black_kingside,
+            castling.black_queenside,
+        ].into_iter().enumerate() {
+            if allowed {
+                hash ^= KEYS[CASTLING_KEYS + i];
+            }
+        }
+
+        if let Some(ep) = self.en_passant {
+            let (file, _) = board_index_reverse(ep);
+            hash ^= KEYS[EN_PASSANT_KEYS + file];
+        }
+
+        hash
+    }
+}
+
+/// How `UniqueGenerator` remembers the positions it already returned
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Dedup {
+    /// Every hash in a hash set, 8 bytes and some overhead per position
+    HashSet,
+    /// A Bloom filter sized for `expected` positions with the given false positive rate.
+    /// Uses far less memory, but a false positive skips a position that was not seen yet
+    Bloom { expected: usize, false_positive_rate: f64 },
+}
+
+/// A fixed-size set of hashes that can answer "maybe seen" for hashes it never saw
+struct BloomFilter {
+    bits: Vec<u64>,
+    n_bits: u64,
+    n_hashes: u32,
+}
+
+impl BloomFilter {
+    fn new(expected: usize, false_positive_rate: f64) -> Self {
+        let ln2 = std::f64::consts::LN_2;
+        let n_bits = (-(expected.max(1) as f64) * false_positive_rate.ln() / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let n_hashes = ((n_bits as f64 / expected.max(1) as f64) * ln2).round().max(1.0) as u32;
+
+        BloomFilter {
+            bits: vec![0; n_bits.div_ceil(64) as usize],
+            n_bits,
+            n_hashes,
+        }
+    }
+
+    /// Adds `hash` and returns whether it was (maybe) in the filter already
+    fn insert(&mut self, hash: u64) -> bool {
+        // Double hashing: the i-th bit is h1 + i * h2, with h2 odd so it cycles through all bits
+        let h1 = hash;
+        let h2 = hash.rotate_left(32).wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+
+        let mut seen = true;
+        for i in 0..self.n_hashes as u64 {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % self.n_bits;
+            let (word, mask) = ((bit / 64) as usize, 1 << (bit % 64));
+
+            seen &= self.bits[word] & mask != 0;
+            self.bits[word] |= mask;
+        }
+        seen
+    }
+}
+
+enum Seen {
+    Set(HashSet<u64>),
+    Bloom(BloomFilter),
+}
+
+/// Generates random boards like `Board::try_random`, but never returns the same position
+/// twice. Positions are compared by `zobrist_hash`, so two different positions with the
+/// same hash also count as the same
+pub struct UniqueGenerator {
+    config: RandomConfig,
+    seen: Seen,
+}
+
+impl UniqueGenerator {
+    pub fn new(config: RandomConfig, dedup: Dedup) -> Self {
+        let seen = match dedup {
+            Dedup::HashSet => Seen::Set(HashSet::new()),
+            Dedup::Bloom { expected, false_positive_rate } => {
+                assert!(
+                    false_positive_rate > 0.0 && false_positive_rate < 1.0,
+                    "false positive rate out of range: {}", false_positive_rate);
+                Seen::Bloom(BloomFilter::new(expected, false_positive_rate))
+            },
+        };
+
+        UniqueGenerator { config, seen }
+    }
+
+    /// Returns a board that was not returned before. Gives up after `config.max_attempts`
+    /// duplicates in a row, e.g. when the material has no new positions left
+    pub fn next_board<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<Board, GenerateError> {
+        for _ in 0..self.config.max_attempts {
+            let board = Board::try_random(rng, &self.config)?;
+            let hash = board.zobrist_hash();
+
+            let is_new = match &mut self.seen {
+                Seen::Set(set) => set.insert(hash),
+                Seen::Bloom(filter) => !filter.insert(hash),
+            };
+            if is_new {
+                return Ok(board);
+            }
+        }
+
+        Err(GenerateError::AttemptsExhausted { attempts: self.config.max_attempts })
+    }
+}
@@ -0,0 +1,55 @@
+use fen_generator::{Board, Color, Piece, PieceType, RandomConfig, Square};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+const PIECE_TYPES: [PieceType; 6] = [
+    PieceType::Pawn,
+    PieceType::Rook,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Queen,
+    PieceType::King,
+];
+
+/// Checks every bitboard against the mailbox
+fn assert_views_agree(board: &Board) {
+    for index in 0..64 {
+        let square = Square::from_index(index).unwrap();
+        let bit = 1u64 << index;
+
+        for color in [Color::White, Color::Black] {
+            for piece_type in PIECE_TYPES {
+                let piece = Piece::new(piece_type, color);
+                assert_eq!(board.bitboard(piece) & bit != 0, board.piece_at(square) == Some(piece), "{}", board.to_str_fen());
+            }
+            let has_color = board.piece_at(square).is_some_and(|piece| piece.color == color);
+            assert_eq!(board.color_bitboard(color) & bit != 0, has_color);
+        }
+        assert_eq!(board.occupied() & bit != 0, board.piece_at(square).is_some());
+    }
+}
+
+#[test]
+fn bitboards_follow_the_mailbox() {
+    let mut rng = ChaCha8Rng::seed_from_u64(2);
+
+    for _ in 0..200 {
+        let mut board = Board::random(&mut rng, &RandomConfig::default());
+        assert_views_agree(&board);
+
+        // Captures, promotions and en passant all go through make_move
+        for _ in 0..20 {
+            let moves = board.legal_moves();
+            if moves.is_empty() {break;}
+
+            board.make_move(moves[rng.random_range(0..moves.len())]);
+            assert_views_agree(&board);
+        }
+    }
+
+    let mut board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    assert_views_agree(&board);
+    board.remove_piece(Square::from_name("e2").unwrap());
+    board.set_piece(Square::from_name("d1").unwrap(), Piece::new(PieceType::Knight, Color::Black));
+    assert_views_agree(&board);
+}
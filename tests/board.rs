@@ -0,0 +1,42 @@
+use fen_generator::{Board, Color, Piece, PieceType, Square, START_FEN};
+
+fn square(name: &str) -> Square {
+    Square::from_name(name).unwrap()
+}
+
+#[test]
+fn pieces_can_be_read_and_changed() {
+    let mut board = Board::start_position();
+    let white_pawn = Piece::new(PieceType::Pawn, Color::White);
+    let black_queen = Piece::new(PieceType::Queen, Color::Black);
+
+    assert_eq!(board.piece_at(square("e2")), Some(white_pawn));
+    assert_eq!(board.piece_at(square("d8")), Some(black_queen));
+    assert_eq!(board.piece_at(square("e4")), None);
+
+    // Moving the pawn by hand gives the same placement as the fen after 1. e4
+    assert_eq!(board.remove_piece(square("e2")), Some(white_pawn));
+    assert_eq!(board.remove_piece(square("e2")), None);
+    assert_eq!(board.set_piece(square("e4"), white_pawn), None);
+    assert_eq!(board.to_str_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 1");
+
+    // Setting a piece replaces what stood there, bitboards included
+    assert_eq!(board.set_piece(square("e4"), black_queen), Some(white_pawn));
+    assert_eq!(board.piece_at(square("e4")), Some(black_queen));
+    assert_eq!(board.bitboard(black_queen).count_ones(), 2);
+    assert_eq!(board.bitboard(white_pawn).count_ones(), 7);
+    assert_eq!(board.occupied().count_ones(), 32);
+}
+
+#[test]
+fn side_to_move_can_be_read_and_changed() {
+    let mut board = Board::from_fen(START_FEN).unwrap();
+    assert_eq!(board.side_to_move(), Color::White);
+
+    board.set_side_to_move(Color::Black);
+    assert_eq!(board.side_to_move(), Color::Black);
+    assert_eq!(board.to_str_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1");
+
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 b - - 0 1").unwrap();
+    assert_eq!(board.side_to_move(), Color::Black);
+}
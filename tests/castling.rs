@@ -0,0 +1,71 @@
+use fen_generator::{Board, CastlingRights, Profile, RandomConfig, START_FEN};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Opening positions keep the kings and rooks at home often enough to have rights to give
+fn config(castling_probability: f64) -> RandomConfig {
+    RandomConfig { castling_probability, ..Profile::Opening.config() }
+}
+
+#[test]
+fn random_rights_are_supported() {
+    let mut rng = ChaCha8Rng::seed_from_u64(19);
+    let mut with_rights = 0;
+
+    for _ in 0..500 {
+        let board = Board::random(&mut rng, &config(0.5));
+        let rights = board.castling_rights();
+        assert!(rights.is_subset_of(board.supported_castling()), "{}", board.to_str_fen());
+        assert!(board.is_legal());
+
+        with_rights += (rights != CastlingRights::default()) as usize;
+    }
+    assert!(with_rights > 20, "{}", with_rights);
+}
+
+#[test]
+fn probability_zero_and_one() {
+    let mut rng = ChaCha8Rng::seed_from_u64(20);
+
+    for _ in 0..200 {
+        let board = Board::random(&mut rng, &config(0.0));
+        assert_eq!(board.castling_rights(), CastlingRights::default());
+
+        let board = Board::random(&mut rng, &config(1.0));
+        assert_eq!(board.castling_rights(), board.supported_castling(), "{}", board.to_str_fen());
+    }
+}
+
+#[test]
+fn rights_are_written_in_fen_order() {
+    let mut board = Board::from_fen(START_FEN).unwrap();
+
+    for bits in 0..16 {
+        let rights = CastlingRights {
+            white_kingside: bits & 1 != 0,
+            white_queenside: bits & 2 != 0,
+            black_kingside: bits & 4 != 0,
+            black_queenside: bits & 8 != 0,
+        };
+        board.set_castling_rights(rights);
+
+        let letters = [
+            (rights.white_kingside, 'K'),
+            (rights.white_queenside, 'Q'),
+            (rights.black_kingside, 'k'),
+            (rights.black_queenside, 'q'),
+        ];
+        let expected: String = letters
+            .into_iter()
+            .filter_map(|(allowed, letter)| allowed.then_some(letter))
+            .collect();
+        let expected = if expected.is_empty() { "-".to_string() } else { expected };
+        assert_eq!(board.to_str_fen().split(' ').nth(2), Some(expected.as_str()));
+    }
+
+    // Spot checks of the exact strings
+    board.set_castling_rights(CastlingRights { white_kingside: true, black_queenside: true, ..CastlingRights::default() });
+    assert_eq!(board.to_str_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w Kq - 0 1");
+    board.set_castling_rights(CastlingRights::ALL);
+    assert_eq!(board.to_str_fen(), START_FEN);
+}
@@ -0,0 +1,128 @@
+use std::collections::HashSet;
+
+use fen_generator::{
+    chess960_back_rank, chess960_index, transcendental_back_rank, transcendental_index, Board, CastlingFiles,
+    CastlingRights, Color, PieceType, PlayoutConfig, CHESS960_POSITIONS, TRANSCENDENTAL_SETUPS,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+#[test]
+fn index_518_is_the_standard_start() {
+    assert_eq!(
+        Board::chess960(518).unwrap().to_str_fen(),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    assert_eq!(
+        Board::chess960(0).unwrap().to_str_fen(),
+        "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1");
+    assert!(Board::chess960(CHESS960_POSITIONS).is_none());
+}
+
+#[test]
+fn every_index_gives_a_distinct_valid_setup() {
+    let mut ranks = HashSet::new();
+
+    for index in 0..CHESS960_POSITIONS {
+        let rank = chess960_back_rank(index).unwrap();
+        assert!(ranks.insert(rank));
+        assert_eq!(chess960_index(&rank), Some(index));
+
+        let files = |piece_type: PieceType| (0..8).filter(move |&file| rank[file] == piece_type);
+        let bishops: Vec<usize> = files(PieceType::Bishop).collect();
+        let rooks: Vec<usize> = files(PieceType::Rook).collect();
+        let king = files(PieceType::King).next().unwrap();
+        assert_eq!(bishops[0] % 2 + bishops[1] % 2, 1, "bishops on the same color in {}", index);
+        assert!(rooks[0] < king && king < rooks[1], "king not between the rooks in {}", index);
+
+        let board = Board::chess960(index).unwrap();
+        assert!(board.is_legal());
+        assert_eq!(board.chess960_index(), Some(index));
+    }
+
+    assert_eq!(Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap().chess960_index(), None);
+}
+
+#[test]
+fn both_castling_notations_round_trip() {
+    for index in [0, 100, 518, 959] {
+        let board = Board::chess960(index).unwrap();
+
+        assert_eq!(Board::from_fen(&board.to_str_fen()).unwrap(), board);
+        assert_eq!(Board::from_fen(&board.to_str_shredder_fen()).unwrap(), board);
+    }
+
+    assert_eq!(Board::chess960(100).unwrap().to_str_shredder_fen(), "qbbnrnkr/pppppppp/8/8/8/8/PPPPPPPP/QBBNRNKR w HEhe - 0 1");
+}
+
+#[test]
+fn x_fen_names_inner_rooks_by_file() {
+    // The g-rook castles, but the h-rook is further out, so "K" would mean the h-rook
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K1RR w G - 0 1").unwrap();
+    assert_eq!(board.castling_files(Color::White), CastlingFiles { king: 4, kingside_rook: 6, queenside_rook: 0 });
+    assert_eq!(board.to_str_fen(), "4k3/8/8/8/8/8/8/R3K1RR w G - 0 1");
+
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K1RR w KA - 0 1").unwrap();
+    assert_eq!(board.castling_files(Color::White).kingside_rook, 7);
+    assert_eq!(board.to_str_fen(), "4k3/8/8/8/8/8/8/R3K1RR w KQ - 0 1");
+    assert_eq!(board.to_str_shredder_fen(), "4k3/8/8/8/8/8/8/R3K1RR w HA - 0 1");
+}
+
+#[test]
+fn double_chess960_reproduces_from_its_pair() {
+    assert_eq!(Board::double_chess960(100, 100), Board::chess960(100));
+    assert!(Board::double_chess960(0, CHESS960_POSITIONS).is_none());
+
+    let board = Board::double_chess960(0, 959).unwrap();
+    assert!(board.is_legal());
+    assert_eq!(board.to_str_shredder_fen(), "rkrnnqbb/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w HFca - 0 1");
+    assert_eq!(Board::from_fen(&board.to_str_shredder_fen()).unwrap(), board);
+    assert_eq!(board.double_chess960_indices(), Some((0, 959)));
+    assert_eq!(board.chess960_index(), None);
+
+    for (white, black) in [(1, 2), (518, 0), (777, 333)] {
+        let board = Board::double_chess960(white, black).unwrap();
+        assert_eq!(board.double_chess960_indices(), Some((white, black)));
+    }
+}
+
+#[test]
+fn transcendental_setups_cover_every_king_square() {
+    let mut ranks = HashSet::new();
+
+    for index in 0..TRANSCENDENTAL_SETUPS {
+        let rank = transcendental_back_rank(index).unwrap();
+        assert!(ranks.insert(rank));
+        assert_eq!(transcendental_index(&rank), Some(index));
+
+        // The middle third are the Chess960 setups
+        let chess960 = index.checked_sub(CHESS960_POSITIONS).filter(|&i| i < CHESS960_POSITIONS);
+        assert_eq!(chess960_index(&rank), chess960);
+    }
+    assert!(transcendental_back_rank(TRANSCENDENTAL_SETUPS).is_none());
+
+    let board = Board::transcendental(0, 2879).unwrap();
+    assert!(board.is_legal());
+    assert_eq!(board.to_str_shredder_fen(), "rrknnqbb/pppppppp/8/8/8/8/PPPPPPPP/BBQNNKRR w - - 0 1");
+    assert_eq!(board.transcendental_indices(), Some((0, 2879)));
+    assert_eq!(board.double_chess960_indices(), None);
+}
+
+#[test]
+fn boards_equal_their_fen_round_trip() {
+    let mut board = Board::chess960(0).unwrap();
+    board.set_castling_rights(CastlingRights::default());
+    let parsed = Board::from_fen("bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w - - 0 1").unwrap();
+    assert_eq!(parsed, board);
+    assert_eq!(HashSet::from([parsed]), HashSet::from([board]));
+
+    // Only the kingside right is left, so the queenside rook file no longer counts
+    let mut board = Board::chess960(0).unwrap();
+    board.set_castling_rights(CastlingRights { white_kingside: true, ..CastlingRights::default() });
+    assert_eq!(Board::from_fen(&board.to_str_fen()).unwrap(), board);
+
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    for _ in 0..200 {
+        let board = Board::random_chess960(&mut rng).playout(&mut rng, &PlayoutConfig::fixed(40));
+        assert_eq!(Board::from_fen(&board.to_str_fen()).unwrap(), board, "{}", board.to_str_fen());
+    }
+}
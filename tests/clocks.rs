@@ -0,0 +1,104 @@
+use fen_generator::{Board, ClockMode, Color, EnPassantMode, MaterialSpec, RandomConfig};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Returns how many plies each side played before the position, going by the fullmove number
+fn moves_played(board: &Board) -> [u64; 2] {
+    let full = board.fullmove_number() as u64 - 1;
+    [full + (board.side_to_move() == Color::Black) as u64, full]
+}
+
+#[test]
+fn clocks_fit_the_position() {
+    let mut rng = ChaCha8Rng::seed_from_u64(22);
+    let modes = [ClockMode::Fixed(0), ClockMode::Fixed(7), ClockMode::Fixed(150), ClockMode::Random, ClockMode::Derived];
+
+    for halfmove_clock in modes {
+        for fullmove_number in [ClockMode::Fixed(1), ClockMode::Fixed(30), ClockMode::Fixed(u32::MAX), ClockMode::Random, ClockMode::Derived] {
+            let config = RandomConfig {
+                en_passant: EnPassantMode::Plausible,
+                en_passant_probability: 0.5,
+                halfmove_clock,
+                fullmove_number,
+                ..RandomConfig::default()
+            };
+
+            for _ in 0..100 {
+                let board = Board::random(&mut rng, &config);
+                let fen = board.to_str_fen();
+                let [white, black] = moves_played(&board);
+
+                if board.en_passant().is_some() {
+                    assert_eq!(board.halfmove_clock(), 0, "{}", fen);
+                }
+                assert!(board.halfmove_clock() <= 100, "{}", fen);
+                assert!(board.halfmove_clock() as u64 <= white + black, "{}", fen);
+                if let ClockMode::Fixed(n) = halfmove_clock {
+                    assert!(board.halfmove_clock() <= n, "{}", fen);
+                }
+
+                if let ClockMode::Fixed(n) = fullmove_number {
+                    assert_eq!(board.fullmove_number(), n);
+                }
+            }
+        }
+    }
+}
+
+/// A fullmove number and a halfmove clock
+type Clocks = (u32, u32);
+
+/// Material, then the smallest clocks with white and with black to move
+const DERIVED: [(&str, Clocks, Clocks); 4] = [
+    // Nothing was captured or promoted
+    ("KQRRBBNNPPPPPPPPvKQRRBBNNPPPPPPPP", (1, 0), (1, 0)),
+    // Each side captured 15 pieces
+    ("KvK", (16, 0), (16, 0)),
+    // White also promoted a pawn, which took at least 5 more moves
+    ("KQQvK", (21, 0), (20, 0)),
+    // With white to move, black has no pawns and white is missing nothing, so the last move kept the clock
+    ("KQRRBBNNPPPPPPPPvK", (16, 1), (15, 0)),
+];
+
+#[test]
+fn derived_clocks_are_the_smallest_that_fit() {
+    let mut rng = ChaCha8Rng::seed_from_u64(23);
+
+    for (material, white, black) in DERIVED {
+        let config = RandomConfig {
+            halfmove_clock: ClockMode::Derived,
+            fullmove_number: ClockMode::Derived,
+            ..RandomConfig::from(material.parse::<MaterialSpec>().unwrap())
+        };
+
+        for _ in 0..20 {
+            let board = Board::random(&mut rng, &config);
+            let expected = match board.side_to_move() {
+                Color::White => white,
+                Color::Black => black,
+            };
+            assert_eq!((board.fullmove_number(), board.halfmove_clock()), expected, "{}", board.to_str_fen());
+        }
+    }
+}
+
+#[test]
+fn random_fullmove_numbers_start_at_the_derived_one() {
+    let mut rng = ChaCha8Rng::seed_from_u64(24);
+
+    for (material, (white, _), (black, _)) in DERIVED {
+        let config = RandomConfig {
+            fullmove_number: ClockMode::Random,
+            ..RandomConfig::from(material.parse::<MaterialSpec>().unwrap())
+        };
+
+        for _ in 0..20 {
+            let board = Board::random(&mut rng, &config);
+            let min = match board.side_to_move() {
+                Color::White => white,
+                Color::Black => black,
+            };
+            assert!((min..=min + 100).contains(&board.fullmove_number()), "{}", board.to_str_fen());
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use fen_generator::{Board, EnPassantMode, Move, PieceType, RandomConfig};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+fn squares(fen: &str, mode: EnPassantMode) -> Vec<String> {
+    Board::from_fen(fen).unwrap()
+        .en_passant_squares(mode)
+        .into_iter()
+        .map(|square| square.to_string())
+        .collect()
+}
+
+#[test]
+fn plausible_needs_a_pawn_that_could_have_been_pushed() {
+    assert_eq!(squares("4k3/8/8/3p4/8/8/8/4K3 w - - 0 1", EnPassantMode::Plausible), ["d6"]);
+    assert_eq!(squares("4k3/8/8/8/3Pp3/8/8/4K3 b - - 0 1", EnPassantMode::Plausible), ["d3"]);
+    assert!(squares("4k3/8/8/3p4/8/8/8/4K3 w - - 0 1", EnPassantMode::Never).is_empty());
+
+    // Not on its fourth rank
+    assert!(squares("4k3/8/3p4/8/8/8/8/4K3 w - - 0 1", EnPassantMode::Plausible).is_empty());
+    assert!(squares("4k3/8/8/8/3p4/8/8/4K3 w - - 0 1", EnPassantMode::Plausible).is_empty());
+
+    // Something on the square it passed or came from
+    assert!(squares("4k3/8/3n4/3p4/8/8/8/4K3 w - - 0 1", EnPassantMode::Plausible).is_empty());
+    assert!(squares("4k3/3n4/8/3p4/8/8/8/4K3 w - - 0 1", EnPassantMode::Plausible).is_empty());
+
+    // On d7 the pawn would not block the bishop, so white would have been in check
+    // with black to move
+    assert!(squares("b3k3/8/8/3p4/8/8/8/7K w - - 0 1", EnPassantMode::Plausible).is_empty());
+}
+
+#[test]
+fn legal_needs_a_capture() {
+    // No white pawn next to d5
+    assert!(squares("4k3/8/8/3p4/8/8/8/4K3 w - - 0 1", EnPassantMode::Legal).is_empty());
+    assert_eq!(squares("4k3/8/8/3pP3/8/8/8/4K3 w - - 0 1", EnPassantMode::Legal), ["d6"]);
+
+    // Taking on d6 clears the fifth rank for the rook
+    let pinned = "4k3/8/8/K2pP2r/8/8/8/8 w - - 0 1";
+    assert_eq!(squares(pinned, EnPassantMode::Plausible), ["d6"]);
+    assert!(squares(pinned, EnPassantMode::Legal).is_empty());
+}
+
+#[test]
+fn generated_squares_follow_the_mode() {
+    let mut rng = ChaCha8Rng::seed_from_u64(21);
+
+    for mode in [EnPassantMode::Plausible, EnPassantMode::Legal] {
+        let config = RandomConfig { en_passant: mode, en_passant_probability: 1.0, ..RandomConfig::default() };
+        let mut written = 0;
+
+        for _ in 0..500 {
+            let board = Board::random(&mut rng, &config);
+            let Some(ep) = board.en_passant() else {continue};
+            written += 1;
+            assert!(board.en_passant_squares(mode).contains(&ep), "{}", board.to_str_fen());
+
+            // A pawn move to the empty en passant square is an en passant capture
+            let is_capture = |mv: &Move| {
+                mv.to == ep && board.piece_at(mv.from).is_some_and(|piece| piece.piece_type == PieceType::Pawn)
+            };
+            if mode == EnPassantMode::Legal {
+                assert!(board.legal_moves().iter().any(is_capture), "{}", board.to_str_fen());
+            }
+        }
+        assert!(written > 0);
+    }
+}
@@ -0,0 +1,34 @@
+use std::collections::HashSet;
+
+use fen_generator::{Board, Color, Material, Positions};
+
+fn material(spec: &str) -> Material {
+    spec.parse().unwrap()
+}
+
+#[test]
+fn kvk_has_3612_king_pairs_for_each_side_to_move() {
+    let boards: Vec<Board> = Positions::new(&material("KvK"), false).collect();
+    assert_eq!(boards.len(), 2 * 3612);
+
+    let placements: HashSet<String> = boards.iter()
+        .map(|board| board.to_str_fen().split(' ').next().unwrap().to_string())
+        .collect();
+    assert_eq!(placements.len(), 3612);
+}
+
+#[test]
+fn kvk_has_462_positions_up_to_symmetry() {
+    let boards: Vec<Board> = Positions::new(&material("KvK"), true).collect();
+    assert_eq!(boards.iter().filter(|board| board.side_to_move() == Color::White).count(), 462);
+    assert_eq!(boards.iter().filter(|board| board.side_to_move() == Color::Black).count(), 462);
+}
+
+#[test]
+fn every_enumerated_position_is_legal_and_unique() {
+    let boards: Vec<Board> = Positions::new(&material("KPvK"), false).collect();
+    assert!(boards.iter().all(Board::is_legal));
+
+    let unique: HashSet<&Board> = boards.iter().collect();
+    assert_eq!(unique.len(), boards.len());
+}
@@ -0,0 +1,21 @@
+use fen_generator::{estimate, MaterialSpec, SampleSpace};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+#[test]
+fn kvk_estimate_covers_the_exact_count() {
+    let space = SampleSpace::from_spec(&"KvK".parse::<MaterialSpec>().unwrap());
+    assert_eq!(space.size(), 2.0 * 64.0 * 63.0);
+
+    let estimate = estimate(&mut ChaCha8Rng::seed_from_u64(3), &space, 20_000);
+    let (low, high) = estimate.interval();
+    assert!(low <= 7224.0 && 7224.0 <= high, "{:?}", estimate.interval());
+}
+
+#[test]
+fn ranged_space_is_the_sum_of_its_materials() {
+    let size = |spec: &str| SampleSpace::from_spec(&spec.parse::<MaterialSpec>().unwrap()).size();
+
+    let sum = size("KvK") + size("KRvK") + size("KRRvK");
+    assert!((size("K+0..2RvK") - sum).abs() < 1e-6 * sum);
+}
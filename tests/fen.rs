@@ -0,0 +1,81 @@
+use fen_generator::{Board, FenError, FenErrorKind, FenField};
+
+fn error(fen: &str) -> (FenField, usize, FenErrorKind) {
+    let FenError { field, offset, kind } = Board::from_fen(fen).unwrap_err();
+    (field, offset, kind)
+}
+
+#[test]
+fn placement_errors() {
+    assert_eq!(
+        error("rnbqkbnr/ppppXppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+        (FenField::Placement, 13, FenErrorKind::UnexpectedChar('X')));
+    // Neighbouring empty squares are written as one digit
+    assert_eq!(
+        error("4k3/8/8/8/8/8/8/4K12 w - - 0 1"),
+        (FenField::Placement, 19, FenErrorKind::UnexpectedChar('2')));
+
+    assert_eq!(error("4k2/8/8/8/8/8/8/4K3 w - - 0 1"), (FenField::Placement, 3, FenErrorKind::BadRankLength));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K4 w - - 0 1"), (FenField::Placement, 18, FenErrorKind::BadRankLength));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K2 w - - 0 1"), (FenField::Placement, 19, FenErrorKind::BadRankLength));
+
+    assert_eq!(error("4k3/8/8/8/8/8/4K3 w - - 0 1"), (FenField::Placement, 17, FenErrorKind::BadRankCount));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3/8 w - - 0 1"), (FenField::Placement, 19, FenErrorKind::BadRankCount));
+}
+
+#[test]
+fn side_to_move_errors() {
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 x - - 0 1"), (FenField::SideToMove, 20, FenErrorKind::UnexpectedChar('x')));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 W - - 0 1"), (FenField::SideToMove, 20, FenErrorKind::UnexpectedChar('W')));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3"), (FenField::SideToMove, 19, FenErrorKind::MissingField));
+}
+
+#[test]
+fn castling_errors() {
+    assert_eq!(
+        error("r3k2r/8/8/8/8/8/8/R3K2R w KQkqK - 0 1"),
+        (FenField::Castling, 30, FenErrorKind::DuplicateCastling('K')));
+    // "H" names the same rook as "K"
+    assert_eq!(
+        error("r3k2r/8/8/8/8/8/8/R3K2R w KH - 0 1"),
+        (FenField::Castling, 27, FenErrorKind::DuplicateCastling('H')));
+    assert_eq!(
+        error("4k3/8/8/8/8/8/8/4K3 w Kx - 0 1"),
+        (FenField::Castling, 23, FenErrorKind::UnexpectedChar('x')));
+
+    // A rook can not stand on the file of its king
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w E - 0 1"), (FenField::Castling, 22, FenErrorKind::UnexpectedChar('E')));
+}
+
+#[test]
+fn en_passant_errors() {
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - e4 0 1"), (FenField::EnPassant, 25, FenErrorKind::BadEnPassantRank));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - i6 0 1"), (FenField::EnPassant, 24, FenErrorKind::UnexpectedChar('i')));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - e66 0 1"), (FenField::EnPassant, 26, FenErrorKind::UnexpectedChar('6')));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - e 0 1"), (FenField::EnPassant, 25, FenErrorKind::MissingField));
+}
+
+#[test]
+fn clock_errors() {
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - - x 1"), (FenField::HalfmoveClock, 26, FenErrorKind::UnexpectedChar('x')));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - - -1 1"), (FenField::HalfmoveClock, 26, FenErrorKind::UnexpectedChar('-')));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - - 99999999999 1"), (FenField::HalfmoveClock, 26, FenErrorKind::BadNumber));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - -"), (FenField::HalfmoveClock, 25, FenErrorKind::MissingField));
+
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - - 0 0"), (FenField::FullmoveNumber, 28, FenErrorKind::BadNumber));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - - 0 1x"), (FenField::FullmoveNumber, 29, FenErrorKind::UnexpectedChar('x')));
+}
+
+#[test]
+fn trailing_text_errors() {
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - - 0 1 extra"), (FenField::FullmoveNumber, 30, FenErrorKind::TrailingText));
+    assert_eq!(error("4k3/8/8/8/8/8/8/4K3 w - - 0 1   x"), (FenField::FullmoveNumber, 32, FenErrorKind::TrailingText));
+    assert!(Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1 ").is_ok());
+}
+
+#[test]
+fn errors_name_field_and_offset() {
+    assert_eq!(
+        Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - e4 0 1").unwrap_err().to_string(),
+        "invalid fen en passant square at offset 25: en passant square must be on rank 3 or 6");
+}
@@ -0,0 +1,54 @@
+use fen_generator::{Board, GameState, GenerateError, MaterialSpec, RandomConfig, START_FEN};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+fn state(fen: &str) -> GameState {
+    Board::from_fen(fen).unwrap().game_state()
+}
+
+#[test]
+fn positions_are_classified() {
+    assert_eq!(state(START_FEN), GameState::Quiet);
+    assert_eq!(state("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1"), GameState::Checkmate);
+    assert_eq!(state("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"), GameState::Stalemate);
+    assert_eq!(state("4k3/8/8/8/8/8/8/4R1K1 b - - 0 1"), GameState::Check);
+    assert_eq!(state("4k3/8/3N4/8/8/8/8/4R1K1 b - - 0 1"), GameState::DoubleCheck);
+
+    // Check is exactly one checker, is_check is any number
+    for fen in ["4k3/8/8/8/8/8/8/4R1K1 b - - 0 1", "4k3/8/3N4/8/8/8/8/4R1K1 b - - 0 1"] {
+        assert!(Board::from_fen(fen).unwrap().is_check());
+    }
+}
+
+#[test]
+fn target_state_resamples_until_it_matches() {
+    let mut rng = ChaCha8Rng::seed_from_u64(17);
+
+    for target in [GameState::Quiet, GameState::Check, GameState::DoubleCheck] {
+        let config = RandomConfig { target_state: Some(target), ..RandomConfig::default() };
+        for _ in 0..20 {
+            let board = Board::random(&mut rng, &config);
+            assert!(board.is_legal());
+            assert_eq!(board.game_state(), target, "{}", board.to_str_fen());
+        }
+    }
+
+    let spec: MaterialSpec = "KQvK".parse().unwrap();
+    for target in [GameState::Checkmate, GameState::Stalemate] {
+        let config = RandomConfig { target_state: Some(target), ..RandomConfig::from(spec) };
+        let board = Board::random(&mut rng, &config);
+        assert_eq!(board.game_state(), target, "{}", board.to_str_fen());
+    }
+}
+
+#[test]
+fn attempts_run_out_for_impossible_targets() {
+    let mut rng = ChaCha8Rng::seed_from_u64(18);
+    // Bare kings can never give check
+    let spec: MaterialSpec = "KvK".parse().unwrap();
+    let config = RandomConfig { target_state: Some(GameState::Check), max_attempts: 50, ..RandomConfig::from(spec) };
+
+    let error = Board::try_random(&mut rng, &config).unwrap_err();
+    assert_eq!(error, GenerateError::AttemptsExhausted { attempts: 50 });
+    assert_eq!(error.to_string(), "no matching position found in 50 attempts");
+}
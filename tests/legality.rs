@@ -0,0 +1,120 @@
+use fen_generator::{Board, Color, GameState, Piece, PieceType, RandomConfig, Square};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Returns the square of the king of `color`
+fn king(board: &Board, color: Color) -> Square {
+    (0..64)
+        .map(|index| Square::from_index(index).unwrap())
+        .find(|&square| board.piece_at(square) == Some(Piece::new(PieceType::King, color)))
+        .unwrap()
+}
+
+/// Returns the pieces giving check to `color`, found by looking outwards from its king
+/// rather than with the attack tables of the crate
+fn checkers(board: &Board, color: Color) -> Vec<(Square, PieceType)> {
+    let king = king(board, color);
+    let at = |file: i8, rank: i8| if (0..8).contains(&file) && (0..8).contains(&rank) {
+        Square::new(file as u8, rank as u8)
+    } else {
+        None
+    };
+    let (file, rank) = (king.file() as i8, king.rank() as i8);
+    let forward = if color == Color::White { 1 } else { -1 };
+    let mut found = Vec::new();
+
+    for (deltas, piece_type) in [
+        (&[(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)][..], PieceType::Knight),
+        (&[(-1, forward), (1, forward)][..], PieceType::Pawn),
+    ] {
+        for &(df, dr) in deltas {
+            if let Some(square) = at(file + df, rank + dr)
+                && board.piece_at(square) == Some(Piece::new(piece_type, !color)) {
+                found.push((square, piece_type));
+            }
+        }
+    }
+
+    for (deltas, slider) in [
+        ([(1, 0), (-1, 0), (0, 1), (0, -1)], PieceType::Rook),
+        ([(1, 1), (1, -1), (-1, 1), (-1, -1)], PieceType::Bishop),
+    ] {
+        for (df, dr) in deltas {
+            let mut step = 1;
+            while let Some(square) = at(file + step * df, rank + step * dr) {
+                if let Some(piece) = board.piece_at(square) {
+                    if piece.color != color && (piece.piece_type == slider || piece.piece_type == PieceType::Queen) {
+                        found.push((square, piece.piece_type));
+                    }
+                    break;
+                }
+                step += 1;
+            }
+        }
+    }
+
+    found
+}
+
+/// Returns whether `a`, `b` and `c` lie on one rank, file or diagonal
+fn aligned(a: Square, b: Square, c: Square) -> bool {
+    let (ax, ay) = (a.file() as i32, a.rank() as i32);
+    let (bx, by) = (b.file() as i32, b.rank() as i32);
+    let (cx, cy) = (c.file() as i32, c.rank() as i32);
+
+    let on_line = |dx: i32, dy: i32| dx == 0 || dy == 0 || dx.abs() == dy.abs();
+    (bx - ax) * (cy - ay) == (by - ay) * (cx - ax) && on_line(bx - ax, by - ay)
+}
+
+fn assert_possible_checks(board: &Board) {
+    let fen = board.to_str_fen();
+    let us = board.side_to_move();
+
+    assert!(checkers(board, !us).is_empty(), "side not to move in check: {}", fen);
+
+    let checks = checkers(board, us);
+    assert!(checks.len() <= 2, "triple check: {}", fen);
+    if let [(a, a_type), (b, b_type)] = checks[..] {
+        let is_slider = |piece_type| matches!(piece_type, PieceType::Rook | PieceType::Bishop | PieceType::Queen);
+        assert!(is_slider(a_type) || is_slider(b_type), "double check without a slider: {}", fen);
+        assert!(!aligned(king(board, us), a, b), "double check along one line: {}", fen);
+    }
+}
+
+#[test]
+fn generated_positions_are_legal() {
+    let mut rng = ChaCha8Rng::seed_from_u64(26);
+    let double_checks = RandomConfig { target_state: Some(GameState::DoubleCheck), ..RandomConfig::default() };
+
+    for config in [RandomConfig::default(), double_checks] {
+        for _ in 0..500 {
+            let board = Board::random(&mut rng, &config);
+
+            let pawns = board.bitboard(Piece::new(PieceType::Pawn, Color::White))
+                | board.bitboard(Piece::new(PieceType::Pawn, Color::Black));
+            assert_eq!(pawns & 0xFF00_0000_0000_00FF, 0, "pawn on the first or last rank: {}", board.to_str_fen());
+
+            assert_possible_checks(&board);
+        }
+    }
+}
+
+#[test]
+fn impossible_checks_are_refused() {
+    let legal = |fen: &str| Board::from_fen(fen).unwrap().is_legal();
+
+    let double_check = Board::from_fen("4k3/8/3N4/8/8/8/8/4R1K1 b - - 0 1").unwrap();
+    assert!(double_check.is_legal());
+    assert_eq!(checkers(&double_check, Color::Black).len(), 2);
+
+    // Three checkers
+    assert!(!legal("4k3/8/3N4/8/B7/8/8/4R1K1 b - - 0 1"));
+    // Two knights can not both have moved last
+    assert!(!legal("4k3/2N5/3N4/8/8/8/8/6K1 b - - 0 1"));
+    // Two rooks on the same file through the king
+    assert!(!legal("4R3/8/8/8/4k3/8/8/K3R3 b - - 0 1"));
+    // The side not to move is in check
+    assert!(!legal("4k3/8/8/8/8/8/8/4R1K1 w - - 0 1"));
+    // Pawns on the last rank
+    assert!(!legal("P3k3/8/8/8/8/8/8/4K3 w - - 0 1"));
+}
@@ -0,0 +1,63 @@
+use fen_generator::{
+    Board, Color, MaterialErrorKind, MaterialSpec, Piece, PieceCount, PieceType, RandomConfig, SideMaterial,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Checks that the pieces of `color` on `board` are within `material`
+fn assert_within(board: &Board, color: Color, material: &SideMaterial) {
+    for (piece_type, count) in [
+        (PieceType::Pawn, material.pawns),
+        (PieceType::Knight, material.knights),
+        (PieceType::Bishop, material.bishops),
+        (PieceType::Rook, material.rooks),
+        (PieceType::Queen, material.queens),
+    ] {
+        let n = board.bitboard(Piece::new(piece_type, color)).count_ones() as u8;
+        assert!((count.min..=count.max).contains(&n), "{:?} {:?} in {}", color, piece_type, board.to_str_fen());
+    }
+    assert_eq!(board.bitboard(Piece::new(PieceType::King, color)).count_ones(), 1);
+}
+
+#[test]
+fn parses_exact_and_ranged_material() {
+    let spec: MaterialSpec = "KRPPvKRP".parse().unwrap();
+    assert_eq!(spec.white.rooks, PieceCount::exactly(1));
+    assert_eq!(spec.white.pawns, PieceCount::exactly(2));
+    assert_eq!(spec.black.pawns, PieceCount::exactly(1));
+    assert_eq!(spec.black.queens, PieceCount::exactly(0));
+
+    let spec: MaterialSpec = "K+2..4P v K+R".parse().unwrap();
+    assert_eq!(spec.white.pawns, PieceCount::between(2, 4));
+    assert_eq!(spec.black.rooks, PieceCount::exactly(1));
+
+    let spec: MaterialSpec = "kvk".parse().unwrap();
+    assert_eq!((spec.white, spec.black), (SideMaterial::NONE, SideMaterial::NONE));
+}
+
+#[test]
+fn reports_where_the_spec_is_wrong() {
+    let error = |spec: &str| {
+        let error = spec.parse::<MaterialSpec>().unwrap_err();
+        (error.offset, error.kind)
+    };
+
+    assert_eq!(error("KQvR"), (3, MaterialErrorKind::MissingKing));
+    assert_eq!(error("KXvK"), (1, MaterialErrorKind::UnexpectedChar('X')));
+    assert_eq!(error("K4..2PvK"), (1, MaterialErrorKind::BadCount));
+    assert_eq!(error("KQ"), (2, MaterialErrorKind::UnexpectedEnd));
+}
+
+#[test]
+fn generated_material_stays_in_range() {
+    let mut rng = ChaCha8Rng::seed_from_u64(25);
+    let ranged: MaterialSpec = "K+2..4P+0..1R+1..2N v K+Q+0..3P+0..2B".parse().unwrap();
+
+    for config in [RandomConfig::default(), RandomConfig::from(ranged)] {
+        for _ in 0..300 {
+            let board = Board::random(&mut rng, &config);
+            assert_within(&board, Color::White, &config.white);
+            assert_within(&board, Color::Black, &config.black);
+        }
+    }
+}
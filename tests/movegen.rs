@@ -0,0 +1,58 @@
+use fen_generator::{Board, Move, PieceType, Profile, RandomConfig, Square};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+fn moves(fen: &str) -> Vec<String> {
+    Board::from_fen(fen).unwrap().legal_moves().iter().map(Move::to_string).collect()
+}
+
+#[test]
+fn promotions_have_a_lowercase_suffix() {
+    let promotions = moves("4k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+    for uci in ["a7a8q", "a7a8r", "a7a8b", "a7a8n"] {
+        assert!(promotions.contains(&uci.to_string()), "{}", uci);
+    }
+
+    let mv = Move::from_uci("a7a8n").unwrap();
+    assert_eq!(mv, Move::new(Square::from_name("a7").unwrap(), Square::from_name("a8").unwrap(), Some(PieceType::Knight)));
+    assert_eq!(mv.to_string(), "a7a8n");
+
+    for uci in ["a7a8Q", "a7a8N", "a7a8k", "a7a8p", "a7a8x", "e2e", "e2e4qq", "i2i4", "e2é4"] {
+        assert_eq!(Move::from_uci(uci), None, "{}", uci);
+    }
+}
+
+#[test]
+fn castling_is_written_as_in_uci() {
+    // Standard castling moves the king two squares
+    let standard = moves("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    assert!(standard.contains(&"e1g1".to_string()) && standard.contains(&"e1c1".to_string()));
+
+    // In Chess960 the king takes its own rook
+    let fen = "4k3/8/8/8/8/8/8/4RKR1 w GE - 0 1";
+    let chess960 = moves(fen);
+    assert!(chess960.contains(&"f1g1".to_string()) && chess960.contains(&"f1e1".to_string()));
+
+    let mut board = Board::from_fen(fen).unwrap();
+    board.make_move(Move::from_uci("f1g1").unwrap());
+    assert_eq!(board.to_str_fen(), "4k3/8/8/8/8/8/8/4RRK1 b - - 1 1");
+
+    let mut board = Board::from_fen(fen).unwrap();
+    board.make_move(Move::from_uci("f1e1").unwrap());
+    assert_eq!(board.to_str_fen(), "4k3/8/8/8/8/8/8/2KR2R1 b - - 1 1");
+}
+
+#[test]
+fn uci_round_trips() {
+    let mut rng = ChaCha8Rng::seed_from_u64(24);
+
+    // Opening positions add castling moves
+    for config in [RandomConfig::default(), Profile::Opening.config()] {
+        for _ in 0..200 {
+            let board = Board::random(&mut rng, &config);
+            for mv in board.legal_moves() {
+                assert_eq!(Move::from_uci(&mv.to_string()), Some(mv));
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use fen_generator::{odds_fen, Board, CastlingRights, Color, Odds, Piece, PieceType, Square};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+#[test]
+fn classic_odds() {
+    assert_eq!(odds_fen(Odds::PawnAndMove), "rnbqkbnr/pppppppp/8/8/8/8/PPPPP1PP/RNBQKBNR b KQkq - 0 1");
+    assert_eq!(odds_fen(Odds::Knight), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/R1BQKBNR w KQkq - 0 1");
+    assert_eq!(odds_fen(Odds::Rook), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/1NBQKBNR w Kkq - 0 1");
+    assert_eq!(odds_fen(Odds::Queen), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNB1KBNR w KQkq - 0 1");
+
+    assert_eq!(
+        Board::odds(Odds::PawnAndMove, Color::Black).to_str_fen(),
+        "rnbqkbnr/ppppp1pp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    assert_eq!(
+        Board::odds(Odds::Rook, Color::Black).to_str_fen(),
+        "1nbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQk - 0 1");
+}
+
+#[test]
+fn random_odds_spend_the_budget_on_one_side() {
+    let mut rng = ChaCha8Rng::seed_from_u64(9);
+    let value = |board: &Board, color: Color| -> u32 {
+        (0..64)
+            .filter_map(|index| board.piece_at(Square::from_index(index).unwrap()))
+            .filter(|piece| piece.color == color)
+            .map(|piece| match piece.piece_type {
+                PieceType::Pawn => 1,
+                PieceType::Knight | PieceType::Bishop => 3,
+                PieceType::Rook => 5,
+                PieceType::Queen => 9,
+                PieceType::King => 0,
+            })
+            .sum()
+    };
+
+    for budget in [0, 1, 4, 9, 20, 100] {
+        for _ in 0..50 {
+            let board = Board::random_odds(&mut rng, budget, Color::White);
+            let removed = 39 - value(&board, Color::White);
+
+            assert!(board.is_legal());
+            assert!(removed <= budget);
+            // A pawn always fits while any budget is left
+            if removed < budget {
+                assert_eq!(board.bitboard(Piece::new(PieceType::Pawn, Color::White)), 0);
+            }
+            assert_eq!(value(&board, Color::Black), 39);
+            assert_eq!(board.castling_rights(), board.supported_castling());
+        }
+    }
+
+    // Everything but the king goes with a large enough budget
+    let board = Board::random_odds(&mut rng, 100, Color::White);
+    assert_eq!(board.castling_rights(), CastlingRights { black_kingside: true, black_queenside: true, ..Default::default() });
+    assert_eq!(board.bitboard(Piece::new(PieceType::King, Color::White)), board.color_bitboard(Color::White));
+}
@@ -0,0 +1,65 @@
+//! Perft numbers of the standard test positions from the Chess Programming Wiki
+//! (https://www.chessprogramming.org/Perft_Results). The depths are kept small
+//! so the suite stays quick in debug builds
+
+use fen_generator::{divide, perft, Board};
+
+fn check(fen: &str, expected: &[u64]) {
+    let board = Board::from_fen(fen).unwrap();
+
+    for (depth, &nodes) in expected.iter().enumerate() {
+        assert_eq!(perft(&board, depth as u32 + 1), nodes, "{} at depth {}", fen, depth + 1);
+    }
+}
+
+#[test]
+fn start_position() {
+    check("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", &[20, 400, 8902, 197281]);
+}
+
+#[test]
+fn kiwipete() {
+    check("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", &[48, 2039, 97862]);
+}
+
+#[test]
+fn position_3() {
+    check("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", &[14, 191, 2812, 43238]);
+}
+
+#[test]
+fn position_4() {
+    check("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1", &[6, 264, 9467]);
+    // The same position mirrored, with colors swapped
+    check("r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1", &[6, 264, 9467]);
+}
+
+#[test]
+fn position_5() {
+    check("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8", &[44, 1486, 62379]);
+}
+
+#[test]
+fn position_6() {
+    check("r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10", &[46, 2079, 89890]);
+}
+
+/// Chess960 positions 1 and 2 from the same wiki (castling written in Shredder-FEN)
+#[test]
+fn chess960_positions() {
+    check("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9", &[21, 528, 12189]);
+    check("2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9", &[21, 807, 18002]);
+}
+
+#[test]
+fn divide_adds_up_to_perft() {
+    let board = Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+
+    let counts = divide(&board, 2);
+    assert_eq!(counts.len(), 48);
+    assert_eq!(counts.iter().map(|(_, nodes)| nodes).sum::<u64>(), perft(&board, 2));
+
+    // Depth 0 plays no move, the position itself is the one node
+    assert!(divide(&board, 0).is_empty());
+    assert_eq!(perft(&board, 0), 1);
+}
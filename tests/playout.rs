@@ -0,0 +1,86 @@
+use fen_generator::{Board, Color, GameState, MoveWeights, PlayoutConfig, Square};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+#[test]
+fn playouts_are_legal_and_count_their_plies() {
+    let mut rng = ChaCha8Rng::seed_from_u64(5);
+    let start = Board::start_position();
+
+    for _ in 0..100 {
+        let board = start.playout(&mut rng, &PlayoutConfig { min_plies: 0, max_plies: 60, weights: MoveWeights::default() });
+        assert!(board.is_legal(), "{}", board.to_str_fen());
+
+        // Round trips, so castling, en passant and the clocks were written consistently
+        assert_eq!(Board::from_fen(&board.to_str_fen()).unwrap(), board);
+        assert!(board.fullmove_number() <= 31);
+    }
+
+    // Two plies from the start always end on move 2 with white to move
+    let board = start.playout(&mut rng, &PlayoutConfig::fixed(2));
+    assert_eq!((board.fullmove_number(), board.side_to_move()), (2, Color::White));
+    assert_eq!(start.playout(&mut rng, &PlayoutConfig::fixed(0)), start);
+}
+
+#[test]
+fn playouts_stop_at_the_end_of_the_game() {
+    let mut rng = ChaCha8Rng::seed_from_u64(6);
+
+    let mate = Board::from_fen("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+    assert_eq!(mate.game_state(), GameState::Checkmate);
+    assert_eq!(mate.playout(&mut rng, &PlayoutConfig::fixed(10)), mate);
+
+    // Most checks mate here, and the game stays over once they do
+    let board = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 w - - 0 1").unwrap();
+    let weights = MoveWeights { check: 1000.0, ..MoveWeights::default() };
+    let mut mates = 0;
+    for _ in 0..20 {
+        let after = board.playout(&mut rng, &PlayoutConfig { min_plies: 20, max_plies: 20, weights });
+        if after.game_state() == GameState::Checkmate {
+            mates += 1;
+        }
+    }
+    assert!(mates >= 15);
+}
+
+#[test]
+fn weights_favor_captures() {
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    // The only capture is exd5
+    let board = Board::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+    let weights = MoveWeights { capture: 1000.0, ..MoveWeights::default() };
+
+    let captured = (0..50)
+        .filter(|_| {
+            let after = board.playout(&mut rng, &PlayoutConfig { min_plies: 1, max_plies: 1, weights });
+            after.to_str_fen() == "4k3/8/8/3P4/8/8/8/4K3 b - - 0 1"
+        })
+        .count();
+    assert!(captured >= 45);
+}
+
+#[test]
+fn negative_weights_never_fail() {
+    let mut rng = ChaCha8Rng::seed_from_u64(8);
+    let start = Board::start_position();
+
+    // Every move weighs 0 or less, so the moves are picked uniformly
+    for weights in [
+        MoveWeights { center: -1.0, capture: -5.0, check: -5.0 },
+        MoveWeights { center: -3.0, capture: -3.0, check: -3.0 },
+    ] {
+        for _ in 0..20 {
+            let board = start.playout(&mut rng, &PlayoutConfig { min_plies: 20, max_plies: 20, weights });
+            assert!(board.is_legal(), "{}", board.to_str_fen());
+        }
+    }
+
+    // Moves to the center weigh 0 and are never played
+    let weights = MoveWeights { center: -2.0, ..MoveWeights::default() };
+    for _ in 0..20 {
+        let board = start.playout(&mut rng, &PlayoutConfig { min_plies: 1, max_plies: 1, weights });
+        for square in ["d4", "e4"] {
+            assert_eq!(board.piece_at(Square::from_name(square).unwrap()), None, "{}", board.to_str_fen());
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use fen_generator::{Board, Move};
+
+fn key(fen: &str) -> u64 {
+    Board::from_fen(fen).unwrap().polyglot_key()
+}
+
+/// Returns the key after playing `moves` from the start position
+fn key_after(moves: &str) -> u64 {
+    let mut board = Board::start_position();
+    for mv in moves.split_whitespace() {
+        board.make_move(Move::from_uci(mv).unwrap());
+    }
+    board.polyglot_key()
+}
+
+#[test]
+fn keys_match_the_book_format_description() {
+    assert_eq!(key_after(""), 0x463b96181691fc9c);
+    assert_eq!(key_after("e2e4"), 0x823c9b50fd114196);
+    assert_eq!(key_after("e2e4 d7d5"), 0x0756b94461c50fb0);
+    assert_eq!(key_after("e2e4 d7d5 e4e5"), 0x662fafb965db29d4);
+    assert_eq!(key_after("e2e4 d7d5 e4e5 f7f5"), 0x22a48b5a8e47ff78);
+    assert_eq!(key_after("e2e4 d7d5 e4e5 f7f5 e1e2"), 0x652a607ca3f242c1);
+    assert_eq!(key_after("e2e4 d7d5 e4e5 f7f5 e1e2 e8f7"), 0x00fdd303c946bdd9);
+
+    // The en passant file counts after c2c4, with the b4 pawn next to it
+    assert_eq!(key_after("a2a4 b7b5 h2h4 b5b4 c2c4"), 0x3c8123ea7b067637);
+    assert_eq!(key_after("a2a4 b7b5 h2h4 b5b4 c2c4 b4c3 a1a3"), 0x5c3f9b829b279560);
+}
+
+#[test]
+fn en_passant_only_counts_with_an_adjacent_pawn() {
+    // No black pawn next to e4
+    assert_eq!(
+        key("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"),
+        key("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"));
+
+    // The f4 pawn could take on e3
+    assert_ne!(
+        key("rnbqkbnr/ppppp1pp/8/8/4Pp2/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"),
+        key("rnbqkbnr/ppppp1pp/8/8/4Pp2/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"));
+
+    // Pinned pawns still count, as in Polyglot
+    assert_ne!(
+        key("8/8/8/8/k2Pp2Q/8/8/4K3 b - d3 0 1"),
+        key("8/8/8/8/k2Pp2Q/8/8/4K3 b - - 0 1"));
+}
+
+#[test]
+fn key_covers_turn_and_castling_but_not_clocks() {
+    let start = key("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+    assert_eq!(start, key("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 12 40"));
+    assert_ne!(start, key("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1"));
+    assert_ne!(start, key("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w Qkq - 0 1"));
+}
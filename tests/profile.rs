@@ -0,0 +1,86 @@
+use fen_generator::{Board, Color, Piece, PieceType, Profile, Square};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Returns the ranks (0..8, counted from the side of `color`) of its pieces of `piece_type`
+fn ranks(board: &Board, piece_type: PieceType, color: Color) -> Vec<u8> {
+    (0..64)
+        .map(|index| Square::from_index(index).unwrap())
+        .filter(|&square| board.piece_at(square) == Some(Piece::new(piece_type, color)))
+        .map(|square| match color {
+            Color::White => square.rank(),
+            Color::Black => 7 - square.rank(),
+        })
+        .collect()
+}
+
+fn count(board: &Board, piece_type: PieceType, color: Color) -> usize {
+    ranks(board, piece_type, color).len()
+}
+
+#[test]
+fn names_round_trip() {
+    for profile in Profile::ALL {
+        assert_eq!(profile.name().parse::<Profile>(), Ok(profile));
+        assert_eq!(profile.to_string(), profile.name());
+    }
+    assert!("late-middlegame".parse::<Profile>().is_err());
+}
+
+#[test]
+fn profiles_give_their_material() {
+    let mut rng = ChaCha8Rng::seed_from_u64(11);
+
+    for profile in Profile::ALL {
+        let config = profile.config();
+        for _ in 0..100 {
+            let board = Board::random(&mut rng, &config);
+            assert!(board.is_legal(), "{}", board.to_str_fen());
+
+            for color in [Color::White, Color::Black] {
+                let material = profile.material();
+                let pawns = count(&board, PieceType::Pawn, color);
+                assert!((material.pawns.min as usize..=material.pawns.max as usize).contains(&pawns));
+
+                let minors = count(&board, PieceType::Knight, color) + count(&board, PieceType::Bishop, color);
+                let majors = count(&board, PieceType::Rook, color) + count(&board, PieceType::Queen, color);
+                match profile {
+                    Profile::PawnEndgame => assert_eq!(minors + majors, 0),
+                    Profile::MinorPieceEndgame => assert!(minors >= 1 && majors == 0, "{}", board.to_str_fen()),
+                    _ => {},
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn profiles_place_kings_and_pawns_by_phase() {
+    let mut rng = ChaCha8Rng::seed_from_u64(12);
+    let samples = 300;
+
+    let mut measure = |profile: Profile| -> (usize, f64) {
+        let (mut kings, mut pawn_ranks, mut pawns) = (0, 0, 0);
+        for _ in 0..samples {
+            let board = Board::random(&mut rng, &profile.config());
+            for color in [Color::White, Color::Black] {
+                kings += ranks(&board, PieceType::King, color).iter().filter(|&&rank| rank == 0).count();
+                let own = ranks(&board, PieceType::Pawn, color);
+                pawn_ranks += own.iter().map(|&rank| rank as usize).sum::<usize>();
+                pawns += own.len();
+            }
+        }
+        (kings, pawn_ranks as f64 / pawns as f64)
+    };
+
+    // Kings stay home early on and come out later, pawns advance with the phase
+    let (opening_kings, opening_pawns) = measure(Profile::Opening);
+    let (middlegame_kings, middlegame_pawns) = measure(Profile::Middlegame);
+    let (endgame_kings, endgame_pawns) = measure(Profile::PawnEndgame);
+
+    assert!(opening_kings > 2 * samples * 3 / 4, "{}", opening_kings);
+    assert!(middlegame_kings > 2 * samples / 2, "{}", middlegame_kings);
+    assert!(endgame_kings < 2 * samples / 4, "{}", endgame_kings);
+    assert!(opening_pawns < middlegame_pawns && middlegame_pawns < endgame_pawns);
+    assert!(opening_pawns < 2.0);
+}
@@ -0,0 +1,54 @@
+use fen_generator::{Board, Material, PositionIndex, Positions};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+fn index(spec: &str) -> PositionIndex {
+    PositionIndex::new(&spec.parse::<Material>().unwrap()).unwrap()
+}
+
+#[test]
+fn sizes_count_all_placements() {
+    assert_eq!(index("KvK").size(), 2 * 64 * 63);
+    assert_eq!(index("KPPvK").size(), 2 * (48 * 47 / 2) * 62 * 61);
+    assert_eq!(index("KvK").count_legal(), 2 * 3612);
+}
+
+#[test]
+fn rank_and_unrank_are_inverse() {
+    for spec in ["KvK", "KRvKB", "KPPvKP"] {
+        let index = index(spec);
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+
+        for _ in 0..2000 {
+            let i = rng.random_range(0..index.size());
+            let board = index.unrank(i).unwrap();
+            assert_eq!(index.rank(&board), Some(i), "{}", spec);
+        }
+    }
+
+    let index = index("KQvK");
+    for board in Positions::new(&"KQvK".parse().unwrap(), false).take(5000) {
+        assert_eq!(index.unrank(index.rank(&board).unwrap()).unwrap(), board);
+    }
+}
+
+#[test]
+fn rank_rejects_other_material() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+    assert_eq!(index("KRvK").rank(&board), None);
+    assert_eq!(index("KvK").rank(&board), None);
+    assert!(index("KQvK").rank(&board).is_some());
+    assert_eq!(index("KvK").unrank(2 * 64 * 63), None);
+}
+
+#[test]
+fn samples_are_legal() {
+    let index = index("KRPvKR");
+    let mut rng = ChaCha8Rng::seed_from_u64(2);
+
+    for _ in 0..100 {
+        let board = index.sample(&mut rng, 1000).unwrap();
+        assert!(board.is_legal());
+        assert!(index.rank(&board).is_some());
+    }
+}
@@ -0,0 +1,81 @@
+use fen_generator::{Board, Color, MaterialSpec, RandomConfig, Unreachable, START_FEN};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+fn check(fen: &str) -> Result<(), Unreachable> {
+    Board::from_fen(fen).unwrap().check_reachable()
+}
+
+#[test]
+fn promotions_need_missing_pawns() {
+    assert_eq!(check(START_FEN), Ok(()));
+    assert_eq!(check("4k3/8/8/8/8/8/PPPPPPP1/QQ2K3 w - - 0 1"), Ok(()));
+    assert_eq!(
+        check("QQQQQQQQ/QQk5/8/8/8/8/PPPPPPPP/4K3 w - - 0 1"),
+        Err(Unreachable::TooManyPromotions { color: Color::White, pawns: 8, promoted: 9 }));
+    assert_eq!(
+        check("nnn1k3/pppppppp/8/8/8/8/8/4K3 w - - 0 1"),
+        Err(Unreachable::TooManyPromotions { color: Color::Black, pawns: 8, promoted: 1 }));
+
+    // Seventeen black pieces, counted while checking white before black is looked at
+    assert_eq!(
+        check("rnbqkbnr/pppppppp/8/8/8/8/8/q3K3 w - - 0 1"),
+        Err(Unreachable::TooManyPromotions { color: Color::Black, pawns: 8, promoted: 1 }));
+}
+
+#[test]
+fn bishops_count_per_square_color() {
+    // Three light-squared bishops, so two of them were promoted
+    assert_eq!(check("4k3/8/8/8/8/8/PPPPPP2/1B1BKB2 w - - 0 1"), Ok(()));
+    assert_eq!(
+        check("4k3/8/8/8/8/8/PPPPPPP1/1B1BKB2 w - - 0 1"),
+        Err(Unreachable::TooManyPromotions { color: Color::White, pawns: 7, promoted: 2 }));
+
+    // With b2 and d2 at home the c1-bishop never left, so the one on a3 was promoted
+    assert_eq!(check("4k3/8/8/8/8/B1PP4/PP2PPPP/4K3 w - - 0 1"), Ok(()));
+    assert_eq!(
+        check("4k3/8/8/8/8/B1P5/PP1PPPPP/4K3 w - - 0 1"),
+        Err(Unreachable::TooManyPromotions { color: Color::White, pawns: 8, promoted: 1 }));
+}
+
+#[test]
+fn pawn_files_need_captures() {
+    // The a3-pawn came from the b-file, but the b2-pawn never moved
+    assert_eq!(check("4k3/8/8/8/8/P7/PP6/4K3 w - - 0 1"), Err(Unreachable::PawnFiles { color: Color::White }));
+    assert_eq!(check("4k3/8/8/8/8/P7/P1P5/4K3 w - - 0 1"), Ok(()));
+
+    // Four pawns on the a-file need 1 + 2 + 3 captures
+    assert_eq!(check("rnbqk3/ppppp3/8/P7/P7/P7/P7/4K3 w - - 0 1"), Ok(()));
+    assert_eq!(
+        check("rnbqk3/pppppp2/8/P7/P7/P7/P7/4K3 w - - 0 1"),
+        Err(Unreachable::TooManyPawnCaptures { color: Color::White, captures: 6, missing: 5 }));
+}
+
+#[test]
+fn castling_needs_unmoved_pieces() {
+    assert_eq!(
+        check("4k3/8/8/8/8/8/8/4K3 w K - 0 1"),
+        Err(Unreachable::CastlingWithMovedPiece { color: Color::White }));
+    assert_eq!(check("r3k3/8/8/8/8/8/8/4K3 w q - 0 1"), Ok(()));
+}
+
+#[test]
+fn reachable_generation_handles_too_much_material() {
+    let mut rng = ChaCha8Rng::seed_from_u64(16);
+    let spec: MaterialSpec = "K+8Q v K+9Q+8P".parse().unwrap();
+    let config = RandomConfig { reachable: true, max_attempts: 1000, ..RandomConfig::from(spec) };
+
+    assert!(Board::try_random(&mut rng, &config).is_err());
+}
+
+#[test]
+fn reachable_generation_passes_the_checks() {
+    let mut rng = ChaCha8Rng::seed_from_u64(13);
+    let config = RandomConfig { reachable: true, ..RandomConfig::default() };
+
+    for _ in 0..300 {
+        let board = Board::random(&mut rng, &config);
+        assert!(board.is_legal());
+        assert_eq!(board.check_reachable(), Ok(()), "{}", board.to_str_fen());
+    }
+}
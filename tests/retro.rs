@@ -0,0 +1,89 @@
+use fen_generator::{Board, Move, PieceType, RandomConfig, RetroError, START_FEN};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+fn check(fen: &str) -> Result<String, RetroError> {
+    Board::from_fen(fen).unwrap().check_last_move().map(|un_move| un_move.mv.to_string())
+}
+
+fn un_moves(fen: &str) -> Vec<(String, Option<PieceType>)> {
+    Board::from_fen(fen).unwrap()
+        .un_moves()
+        .into_iter()
+        .map(|un_move| (un_move.mv.to_string(), un_move.captured))
+        .collect()
+}
+
+#[test]
+fn every_played_move_can_be_taken_back() {
+    let mut rng = ChaCha8Rng::seed_from_u64(14);
+
+    for _ in 0..200 {
+        let mut board = Board::random(&mut rng, &RandomConfig::default());
+        for _ in 0..10 {
+            let moves = board.legal_moves();
+            if moves.is_empty() {break;}
+
+            let mv = moves[rng.random_range(0..moves.len())];
+            let before = board.clone();
+            board.make_move(mv);
+
+            // Castling rights and the en passant square are only kept where the
+            // position after the move needs them, so only the pieces have to come back
+            let placement = |board: &Board| board.to_str_fen().split(' ').next().unwrap().to_string();
+            let found = board.un_moves()
+                .into_iter()
+                .any(|un_move| un_move.mv == mv && placement(&un_move.previous) == placement(&before));
+            assert!(found, "{} is missing after {}", mv, before.to_str_fen());
+        }
+    }
+}
+
+#[test]
+fn un_moves_take_back_captures_promotions_and_castling() {
+    let after_e4 = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+    assert_eq!(un_moves(after_e4), [("e2e4".to_string(), None)]);
+
+    let promoted = un_moves("1R2k3/8/8/8/8/8/8/4K3 b - - 0 1");
+    assert!(promoted.contains(&("b7b8r".to_string(), None)));
+    assert!(promoted.contains(&("a7b8r".to_string(), Some(PieceType::Knight))));
+    assert!(!promoted.contains(&("a7b8r".to_string(), Some(PieceType::Pawn))));
+
+    let en_passant = un_moves("4k3/8/3P4/8/8/8/8/4K3 b - - 0 1");
+    assert!(en_passant.contains(&("e5d6".to_string(), Some(PieceType::Pawn))));
+
+    let castled = un_moves("4k3/8/8/8/8/8/8/5RK1 b - - 0 1");
+    assert!(castled.contains(&("e1g1".to_string(), None)));
+
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/5RK1 b - - 0 1").unwrap();
+    let un_move = board.un_moves().into_iter().find(|un_move| un_move.mv == Move::from_uci("e1g1").unwrap()).unwrap();
+    assert_eq!(un_move.previous.to_str_fen(), "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+}
+
+#[test]
+fn failures_are_explained() {
+    assert!(check(START_FEN).is_ok());
+
+    assert_eq!(check("4k3/3P1P2/8/8/8/8/8/4K3 b - - 0 1"), Err(RetroError::DoubleCheckWithoutSlider(PieceType::Pawn, PieceType::Pawn)));
+    assert_eq!(
+        RetroError::DoubleCheckWithoutSlider(PieceType::Pawn, PieceType::Pawn).to_string(),
+        "double check by two pawns cannot arise");
+    assert_eq!(check("4R3/8/8/8/4k3/8/8/K3R3 b - - 0 1"), Err(RetroError::AlignedDoubleCheck));
+    assert_eq!(check("4k2R/8/8/8/8/8/8/4K3 w - - 0 1"), Err(RetroError::MoverInCheck));
+
+    // With both castling rights neither the king nor a rook can have moved
+    assert_eq!(check("4k3/8/8/8/8/8/8/R3K2R b KQ - 0 1"), Err(RetroError::NoLastMove));
+    // The e-pawn can not have come from e7 with a piece standing there
+    assert_eq!(check("4k3/4b3/8/4p3/8/8/8/4K3 w - e6 0 1"), Err(RetroError::NoDoublePush));
+}
+
+#[test]
+fn generation_can_require_a_last_move() {
+    let mut rng = ChaCha8Rng::seed_from_u64(15);
+    let config = RandomConfig { last_move: true, ..RandomConfig::default() };
+
+    for _ in 0..200 {
+        let board = Board::random(&mut rng, &config);
+        assert!(board.check_last_move().is_ok(), "{}", board.to_str_fen());
+    }
+}
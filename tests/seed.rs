@@ -0,0 +1,30 @@
+use fen_generator::{random_fen_seeded, Board, RandomConfig};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+#[test]
+fn same_seed_gives_same_fen() {
+    for seed in [0, 1, 42, u64::MAX] {
+        assert_eq!(random_fen_seeded(seed), random_fen_seeded(seed));
+    }
+}
+
+#[test]
+fn seeded_fens_do_not_depend_on_the_platform() {
+    // These are pinned, so a change here means the output for a seed changed
+    assert_eq!(random_fen_seeded(0), "7R/2n3P1/4K3/2p1Bb2/k2b2P1/2r5/B1RP3P/r7 w - - 0 1");
+    assert_eq!(random_fen_seeded(42), "8/P7/4R3/1p2P3/4P3/PPbK1P1q/1k2PP2/4R3 b - - 0 1");
+}
+
+#[test]
+fn seeded_boards_are_legal_and_round_trip() {
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+
+    for _ in 0..1000 {
+        let board = Board::random(&mut rng, &RandomConfig::default());
+        assert!(board.is_legal());
+
+        let fen = board.to_str_fen();
+        assert_eq!(Board::from_fen(&fen).unwrap(), board);
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::HashSet;
+
+use fen_generator::{Board, Dedup, MaterialSpec, Positions, RandomConfig, UniqueGenerator};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+fn hash(fen: &str) -> u64 {
+    Board::from_fen(fen).unwrap().zobrist_hash()
+}
+
+fn kings_only() -> RandomConfig {
+    RandomConfig::from("KvK".parse::<MaterialSpec>().unwrap())
+}
+
+#[test]
+fn hash_covers_every_field_but_the_clocks() {
+    let start = hash("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+
+    assert_eq!(start, hash("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 7 30"));
+    assert_ne!(start, hash("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"));
+    assert_ne!(start, hash("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQk e3 0 1"));
+    assert_ne!(start, hash("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 1"));
+    assert_ne!(start, hash("rnbqkbnr/pppppppp/8/8/3P4/8/PPP1PPPP/RNBQKBNR b KQkq d3 0 1"));
+}
+
+#[test]
+fn hash_is_unique_for_all_king_positions() {
+    let boards: Vec<Board> = Positions::new(&"KvK".parse().unwrap(), false).collect();
+    let hashes: HashSet<u64> = boards.iter().map(Board::zobrist_hash).collect();
+
+    assert_eq!(hashes.len(), boards.len());
+}
+
+#[test]
+fn unique_generator_returns_every_king_position_once() {
+    let mut rng = ChaCha8Rng::seed_from_u64(3);
+    let mut generator = UniqueGenerator::new(kings_only(), Dedup::HashSet);
+
+    let fens: HashSet<String> = (0..2 * 3612)
+        .map(|_| generator.next_board(&mut rng).unwrap().to_str_fen())
+        .collect();
+    assert_eq!(fens.len(), 2 * 3612);
+
+    assert!(generator.next_board(&mut rng).is_err());
+}
+
+#[test]
+fn bloom_filter_never_repeats() {
+    let mut rng = ChaCha8Rng::seed_from_u64(3);
+    let dedup = Dedup::Bloom { expected: 3000, false_positive_rate: 0.001 };
+    let mut generator = UniqueGenerator::new(kings_only(), dedup);
+
+    let fens: HashSet<String> = (0..3000)
+        .map(|_| generator.next_board(&mut rng).unwrap().to_str_fen())
+        .collect();
+    assert_eq!(fens.len(), 3000);
+}